@@ -9,13 +9,13 @@ use clap::{Parser, Subcommand};
 
 use lindera::{
     analyzer::Analyzer,
-    builder::{build_dictionary, build_user_dictionary},
+    builder::{build_dictionary, build_dictionary_bundle, build_user_dictionary},
     error::{LinderaError, LinderaErrorKind},
     mode::Mode,
     tokenizer::{
         DictionaryConfig, Tokenizer, TokenizerConfig, UserDictionaryConfig, CONTAINED_DICTIONARIES,
     },
-    DictionaryKind, LinderaResult,
+    DictionaryKind, LinderaResult, Token,
 };
 
 #[derive(Debug, Parser)]
@@ -62,7 +62,7 @@ struct TokenizeArgs {
         short = 'm',
         long = "mode",
         default_value = "normal",
-        help = "Tokenization mode. normal"
+        help = "Tokenization mode. normal, decompose, search or extended"
     )]
     mode: Mode,
     #[clap(
@@ -72,6 +72,16 @@ struct TokenizeArgs {
         help = "Output format"
     )]
     output_format: String,
+    #[clap(
+        long = "named-details",
+        help = "Output the details in JSON as an object keyed by the field names of the dictionary"
+    )]
+    named_details: bool,
+    #[clap(
+        long = "dump-lattice",
+        help = "Dump the lattice of each sentence instead of the tokens. dot or json"
+    )]
+    dump_lattice: Option<LatticeFormat>,
     #[clap(help = "Input text file path")]
     input_file: Option<PathBuf>,
 }
@@ -92,6 +102,11 @@ struct AnalyzeArgs {
         help = "Output format"
     )]
     output_format: String,
+    #[clap(
+        long = "named-details",
+        help = "Output the details in JSON as an object keyed by the field names of the dictionary"
+    )]
+    named_details: bool,
     #[clap(help = "Input text file path")]
     input_file: Option<PathBuf>,
 }
@@ -101,6 +116,12 @@ struct AnalyzeArgs {
 struct BuildArgs {
     #[clap(short = 'u', long = "build-user-dic", help = "Build user dictionary")]
     build_user_dic: bool,
+    #[clap(
+        short = 'b',
+        long = "bundle",
+        help = "Build a single-file dictionary bundle at the destination path"
+    )]
+    bundle: bool,
     #[clap(short = 't', long = "dic-type", help = "Dictionary type")]
     dic_type: DictionaryKind,
     #[clap(help = "Dictionary source path")]
@@ -130,6 +151,28 @@ impl FromStr for Format {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+/// Lattice dump format
+pub enum LatticeFormat {
+    Dot,
+    Json,
+}
+
+impl FromStr for LatticeFormat {
+    type Err = LinderaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(LatticeFormat::Dot),
+            "json" => Ok(LatticeFormat::Json),
+            _ => {
+                Err(LinderaErrorKind::Args
+                    .with_error(anyhow::anyhow!("Invalid lattice format: {}", s)))
+            }
+        }
+    }
+}
+
 fn main() -> LinderaResult<()> {
     let args = Args::parse();
 
@@ -166,6 +209,7 @@ fn tokenize(args: TokenizeArgs) -> LinderaResult<()> {
         dictionary: dictionary_conf,
         user_dictionary: user_dictionary_conf,
         mode: args.mode,
+        ..TokenizerConfig::default()
     };
 
     // create tokenizer
@@ -194,6 +238,26 @@ fn tokenize(args: TokenizeArgs) -> LinderaResult<()> {
             break;
         }
 
+        if let Some(lattice_format) = args.dump_lattice {
+            let dumps = tokenizer.dump_lattice(text.trim())?;
+            match lattice_format {
+                LatticeFormat::Dot => {
+                    for dump in dumps {
+                        print!("{}", dump.to_dot());
+                    }
+                }
+                LatticeFormat::Json => {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&dumps).map_err(|err| {
+                            LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err))
+                        })?
+                    );
+                }
+            }
+            continue;
+        }
+
         match output_format {
             Format::Mecab => {
                 let tokens = tokenizer.tokenize_with_details(text.trim())?;
@@ -216,10 +280,9 @@ fn tokenize(args: TokenizeArgs) -> LinderaResult<()> {
                 let tokens = tokenizer.tokenize_with_details(text.trim())?;
                 let mut tokens_json = Vec::new();
                 for token in tokens {
-                    let word_details = token.details.unwrap_or_default();
                     let token_info = serde_json::json!({
                         "text": token.text,
-                        "details": word_details,
+                        "details": details_json(&token, args.named_details),
                         "byte_start": token.byte_start,
                         "byte_end": token.byte_end,
                     });
@@ -304,10 +367,9 @@ fn analyze(args: AnalyzeArgs) -> LinderaResult<()> {
             Format::Json => {
                 let mut tokens_json = Vec::new();
                 for token in tokens {
-                    let word_details = token.details.unwrap_or_default();
                     let token_info = serde_json::json!({
                         "text": token.text,
-                        "details": word_details,
+                        "details": details_json(&token, args.named_details),
                         "byte_start": token.byte_start,
                         "byte_end": token.byte_end,
                     });
@@ -335,9 +397,28 @@ fn analyze(args: AnalyzeArgs) -> LinderaResult<()> {
 
     Ok(())
 }
+
+/// Returns the details of the token as an array, or if `named` is true,
+/// as an object keyed by the field names of the dictionary schema.
+/// Tokens without a schema always give an array.
+fn details_json(token: &Token, named: bool) -> serde_json::Value {
+    let named_details = if named { token.named_details() } else { None };
+    match named_details {
+        Some(named_details) => serde_json::Value::Object(
+            named_details
+                .into_iter()
+                .map(|(name, detail)| (name.to_string(), serde_json::json!(detail)))
+                .collect(),
+        ),
+        None => serde_json::json!(token.details.clone().unwrap_or_default()),
+    }
+}
+
 fn build(args: BuildArgs) -> LinderaResult<()> {
     if args.build_user_dic {
         build_user_dictionary(args.dic_type, &args.src_path, &args.dest_path)
+    } else if args.bundle {
+        build_dictionary_bundle(args.dic_type, &args.src_path, &args.dest_path)
     } else {
         build_dictionary(args.dic_type, &args.src_path, &args.dest_path)
     }
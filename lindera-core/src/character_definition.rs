@@ -1,32 +1,32 @@
 use std::collections::{BTreeSet, HashMap};
 
-use byteorder::{ByteOrder, LittleEndian};
-use encoding_rs::UTF_16LE;
 use serde::{Deserialize, Serialize};
 
 use crate::{error::LinderaErrorKind, LinderaResult};
 
 const DEFAULT_CATEGORY_NAME: &str = "DEFAULT";
 
-fn ucs2_to_unicode(ucs2_codepoint: u16) -> LinderaResult<u32> {
-    let mut buf = [0u8; 2];
-    LittleEndian::write_u16(&mut buf[..], ucs2_codepoint);
-
-    let s = UTF_16LE.decode(&buf[..]).0.into_owned();
-    let chrs: Vec<char> = s.chars().collect();
-
-    match chrs.len() {
-        1 => Ok(chrs[0] as u32),
-        _ => Err(LinderaErrorKind::Parse.with_error(anyhow::anyhow!("unusual char length"))),
-    }
-}
+/// The largest code point of Unicode.
+const MAX_CODEPOINT: u32 = 0x10FFFF;
+/// The surrogate code points, which are not characters.
+const SURROGATES: std::ops::RangeInclusive<u32> = 0xD800..=0xDFFF;
 
 fn parse_hex_codepoint(s: &str) -> LinderaResult<u32> {
     let removed_0x = s.trim_start_matches("0x");
-    let ucs2_codepoint = u16::from_str_radix(removed_0x, 16)
+    let codepoint = u32::from_str_radix(removed_0x, 16)
         .map_err(|err| LinderaErrorKind::Parse.with_error(anyhow::anyhow!(err)))?;
+    if codepoint > MAX_CODEPOINT {
+        return Err(
+            LinderaErrorKind::Parse.with_error(anyhow::anyhow!("code point out of range: {}", s))
+        );
+    }
+    if SURROGATES.contains(&codepoint) {
+        return Err(
+            LinderaErrorKind::Parse.with_error(anyhow::anyhow!("surrogate code point: {}", s))
+        );
+    }
 
-    ucs2_to_unicode(ucs2_codepoint)
+    Ok(codepoint)
 }
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
@@ -246,7 +246,7 @@ impl CharacterDefinitionsBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::character_definition::LookupTable;
+    use crate::character_definition::{CharacterDefinitionsBuilder, LookupTable};
 
     #[test]
     fn test_lookup_table() {
@@ -265,6 +265,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_non_bmp_ranges() {
+        let mut char_definitions_builder = CharacterDefinitionsBuilder::default();
+        char_definitions_builder
+            .parse(
+                "DEFAULT 0 1 0\n\
+                 KANJI 0 0 2\n\
+                 EMOJI 1 1 0\n\
+                 0x4E00..0x9FFF KANJI\n\
+                 0x20000..0x2A6DF KANJI\n\
+                 0x1F300..0x1FAFF EMOJI\n",
+            )
+            .unwrap();
+        let char_definitions = char_definitions_builder.build();
+
+        let category_name = |c: char| {
+            char_definitions
+                .lookup_categories(c)
+                .iter()
+                .map(|&category_id| char_definitions.category_name(category_id))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(category_name('𠮷'), vec!["KANJI"]);
+        assert_eq!(category_name('吉'), vec!["KANJI"]);
+        assert_eq!(category_name('\u{1F600}'), vec!["EMOJI"]);
+        assert_eq!(category_name('\u{2A6E0}'), vec!["DEFAULT"]);
+        assert_eq!(category_name('\u{10FFFF}'), vec!["DEFAULT"]);
+
+        let mut char_definitions_builder = CharacterDefinitionsBuilder::default();
+        assert!(char_definitions_builder
+            .parse("DEFAULT 0 1 0\n0x110000 DEFAULT\n")
+            .is_err());
+        let mut char_definitions_builder = CharacterDefinitionsBuilder::default();
+        assert!(char_definitions_builder
+            .parse("DEFAULT 0 1 0\n0xD800 DEFAULT\n")
+            .is_err());
+        let mut char_definitions_builder = CharacterDefinitionsBuilder::default();
+        assert!(char_definitions_builder
+            .parse("DEFAULT 0 1 0\n0xD000..0xDFFF DEFAULT\n")
+            .is_err());
+    }
+
     //    #[test]
     //    fn test_bisa() {
     //        let char_definitions = CharacterDefinitions::load();
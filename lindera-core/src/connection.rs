@@ -1,9 +1,11 @@
 use byteorder::{ByteOrder, LittleEndian};
 use serde::{Deserialize, Serialize};
 
+use crate::dictionary_data::DictionaryData;
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ConnectionCostMatrix {
-    pub costs_data: Vec<u8>,
+    pub costs_data: DictionaryData,
     pub backward_size: u32,
 }
 
@@ -11,7 +13,21 @@ impl ConnectionCostMatrix {
     pub fn load(conn_data: &[u8]) -> ConnectionCostMatrix {
         let backward_size = LittleEndian::read_i16(&conn_data[2..4]);
         ConnectionCostMatrix {
-            costs_data: conn_data[4..].to_vec(),
+            costs_data: DictionaryData::Owned(conn_data[4..].to_vec()),
+            backward_size: backward_size as u32,
+        }
+    }
+
+    /// Same as `load`, but borrows the costs without copying them.
+    pub fn load_static(conn_data: &'static [u8]) -> ConnectionCostMatrix {
+        Self::from_data(DictionaryData::Static(conn_data))
+    }
+
+    /// Same as `load`, but keeps borrowed data, e.g. a memory-mapped file, borrowed.
+    pub fn from_data(conn_data: DictionaryData) -> ConnectionCostMatrix {
+        let backward_size = LittleEndian::read_i16(&conn_data[2..4]);
+        ConnectionCostMatrix {
+            costs_data: conn_data.slice_from(4),
             backward_size: backward_size as u32,
         }
     }
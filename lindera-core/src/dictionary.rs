@@ -1,16 +1,93 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    character_definition::CharacterDefinitions, connection::ConnectionCostMatrix,
-    prefix_dict::PrefixDict, unknown_dictionary::UnknownDictionary,
+    character_definition::CharacterDefinitions,
+    connection::ConnectionCostMatrix,
+    dictionary_bundle::DictionaryBundle,
+    dictionary_data::DictionaryData,
+    dictionary_schema::DictionarySchema,
+    error::{LinderaError, LinderaErrorKind},
+    prefix_dict::PrefixDict,
+    unknown_dictionary::UnknownDictionary,
+    LinderaResult,
 };
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum DictionaryKind {
+    #[serde(rename = "ipadic")]
+    IPADIC,
+    #[serde(rename = "unidic")]
+    UniDic,
+    #[serde(rename = "ko-dic")]
+    KoDic,
+    #[serde(rename = "cc-cedict")]
+    CcCedict,
+}
+
+impl FromStr for DictionaryKind {
+    type Err = LinderaError;
+    fn from_str(input: &str) -> Result<DictionaryKind, Self::Err> {
+        match input {
+            "ipadic" => Ok(DictionaryKind::IPADIC),
+            "unidic" => Ok(DictionaryKind::UniDic),
+            "ko-dic" => Ok(DictionaryKind::KoDic),
+            "cc-cedict" => Ok(DictionaryKind::CcCedict),
+            _ => Err(LinderaErrorKind::DictionaryKindError
+                .with_error(anyhow::anyhow!("Invalid dictionary kind: {}", input))),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Dictionary {
-    pub dict: PrefixDict<Vec<u8>>,
+    pub dict: PrefixDict,
     pub cost_matrix: ConnectionCostMatrix,
     pub char_definitions: CharacterDefinitions,
     pub unknown_dictionary: UnknownDictionary,
-    pub words_idx_data: Vec<u8>,
-    pub words_data: Vec<u8>,
+    pub words_idx_data: DictionaryData,
+    pub words_data: DictionaryData,
+    /// The kind of the dictionary, which tells the layout of the word details.
+    /// `None` if the dictionary is not one of the known kinds.
+    pub kind: Option<DictionaryKind>,
+    /// The names of the fields of the word details.
+    pub schema: DictionarySchema,
+}
+
+impl Dictionary {
+    /// Loads the dictionary from the bytes of a dictionary bundle.
+    /// The bytes are copied once and shared by all the sections.
+    ///
+    /// # Arguments
+    ///
+    /// * `data`: The content of the dictionary bundle.
+    ///
+    /// returns: Result<Dictionary, LinderaError>
+    ///
+    pub fn from_bytes(data: &[u8]) -> LinderaResult<Dictionary> {
+        Self::from_data(DictionaryData::shared(data.to_vec()))
+    }
+
+    /// Same as `from_bytes`, but keeps borrowed data, e.g. a memory-mapped file, borrowed.
+    pub fn from_data(data: DictionaryData) -> LinderaResult<Dictionary> {
+        Self::from_bundle(&DictionaryBundle::read(data)?)
+    }
+
+    /// Loads the dictionary from the sections of the bundle.
+    pub fn from_bundle(bundle: &DictionaryBundle) -> LinderaResult<Dictionary> {
+        Ok(Dictionary {
+            dict: PrefixDict::from_data(
+                bundle.section("dict.da")?.clone(),
+                bundle.section("dict.vals")?.clone(),
+            ),
+            cost_matrix: ConnectionCostMatrix::from_data(bundle.section("matrix.mtx")?.clone()),
+            char_definitions: CharacterDefinitions::load(bundle.section("char_def.bin")?)?,
+            unknown_dictionary: UnknownDictionary::load(bundle.section("unk.bin")?)?,
+            words_idx_data: bundle.section("dict.wordsidx")?.clone(),
+            words_data: bundle.section("dict.words")?.clone(),
+            kind: bundle.header.kind.parse().ok(),
+            schema: DictionarySchema::load(bundle.section("schema.bin")?)?,
+        })
+    }
 }
@@ -1,7 +1,8 @@
 use std::path::Path;
 
 use crate::{
-    character_definition::CharacterDefinitions, user_dictionary::UserDictionary, LinderaResult,
+    character_definition::CharacterDefinitions, dictionary_schema::DictionarySchema,
+    user_dictionary::UserDictionary, LinderaResult,
 };
 
 pub trait DictionaryBuilder {
@@ -21,4 +22,6 @@ pub trait DictionaryBuilder {
     fn build_dict(&self, input_dir: &Path, output_dir: &Path) -> LinderaResult<()>;
     fn build_cost_matrix(&self, input_dir: &Path, output_dir: &Path) -> LinderaResult<()>;
     fn build_user_dict(&self, input_file: &Path) -> LinderaResult<UserDictionary>;
+    fn schema(&self) -> DictionarySchema;
+    fn build_schema(&self, output_dir: &Path) -> LinderaResult<()>;
 }
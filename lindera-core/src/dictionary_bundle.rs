@@ -0,0 +1,210 @@
+use std::{collections::HashMap, io::Write};
+
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
+
+use crate::{
+    dictionary_data::DictionaryData,
+    dictionary_header::{read_data_with_header, write_with_header, DictionaryHeader},
+    error::LinderaErrorKind,
+    LinderaResult,
+};
+
+/// The magic bytes at the beginning of the data of a dictionary bundle.
+pub const BUNDLE_MAGIC: &[u8; 8] = b"LDBUNDLE";
+
+/// The sections of a dictionary, named after the files the builders write.
+pub const DICTIONARY_SECTIONS: [&str; 8] = [
+    "dict.da",
+    "dict.vals",
+    "matrix.mtx",
+    "char_def.bin",
+    "unk.bin",
+    "dict.wordsidx",
+    "dict.words",
+    "schema.bin",
+];
+
+/// A single file that holds all the sections of a dictionary.
+///
+/// The file starts with the dictionary header, followed by the bundle magic,
+/// the number of sections, the table of sections (name, offset and length)
+/// and the data of the sections. The offsets are relative to the bundle magic.
+pub struct DictionaryBundle {
+    pub header: DictionaryHeader,
+    sections: HashMap<String, DictionaryData>,
+}
+
+impl DictionaryBundle {
+    /// Writes the sections as a bundle
+    ///
+    /// # Arguments
+    ///
+    /// * `writer`: The destination.
+    /// * `kind`: The kind of the dictionary, e.g. `ipadic`.
+    /// * `builder_version`: The version of the builder.
+    /// * `sections`: The names and the data of the sections.
+    ///
+    /// returns: Result<(), LinderaError>
+    ///
+    pub fn write<W: Write>(
+        writer: &mut W,
+        kind: &str,
+        builder_version: &str,
+        sections: &[(&str, &[u8])],
+    ) -> LinderaResult<()> {
+        let table_len: usize = sections
+            .iter()
+            .map(|(name, _)| 2 + name.len() + 8 + 8)
+            .sum();
+        let mut offset = (BUNDLE_MAGIC.len() + 4 + table_len) as u64;
+
+        let mut buffer = Vec::new();
+        buffer.extend_from_slice(BUNDLE_MAGIC);
+        buffer
+            .write_u32::<LittleEndian>(sections.len() as u32)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+        for (name, data) in sections {
+            buffer
+                .write_u16::<LittleEndian>(name.len() as u16)
+                .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+            buffer.extend_from_slice(name.as_bytes());
+            buffer
+                .write_u64::<LittleEndian>(offset)
+                .and_then(|_| buffer.write_u64::<LittleEndian>(data.len() as u64))
+                .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+            offset += data.len() as u64;
+        }
+        for (_, data) in sections {
+            buffer.extend_from_slice(data);
+        }
+
+        write_with_header(writer, kind, builder_version, false, &buffer)
+    }
+
+    /// Parses the bundle.
+    /// The sections borrow the data if it is borrowed, e.g. a memory-mapped file.
+    ///
+    /// # Arguments
+    ///
+    /// * `data`: The content of the bundle file.
+    ///
+    /// returns: Result<DictionaryBundle, LinderaError>
+    ///
+    pub fn read(data: DictionaryData) -> LinderaResult<Self> {
+        let (header, data) = read_data_with_header(data)?;
+        if header.compressed {
+            return Err(load_error(
+                "compressed dictionary bundles are not supported",
+            ));
+        }
+        if data.len() < BUNDLE_MAGIC.len() + 4 || &data[..BUNDLE_MAGIC.len()] != BUNDLE_MAGIC {
+            return Err(load_error("the file is not a dictionary bundle"));
+        }
+
+        let mut offset = BUNDLE_MAGIC.len();
+        let count = LittleEndian::read_u32(&data[offset..]) as usize;
+        offset += 4;
+
+        // The count comes from the file, so it must not size the allocation on its own.
+        let mut sections = HashMap::with_capacity(count.min(DICTIONARY_SECTIONS.len()));
+        for _ in 0..count {
+            let name_len = data
+                .get(offset..offset + 2)
+                .map(LittleEndian::read_u16)
+                .ok_or_else(|| load_error("the table of sections is truncated"))?
+                as usize;
+            offset += 2;
+            let name = data
+                .get(offset..offset + name_len)
+                .ok_or_else(|| load_error("the table of sections is truncated"))?;
+            let name = std::str::from_utf8(name)
+                .map_err(|_| load_error("the name of a section is not valid UTF-8"))?
+                .to_string();
+            offset += name_len;
+            let range = data
+                .get(offset..offset + 16)
+                .ok_or_else(|| load_error("the table of sections is truncated"))?;
+            let start = LittleEndian::read_u64(range) as usize;
+            let end = start.saturating_add(LittleEndian::read_u64(&range[8..]) as usize);
+            offset += 16;
+            if end > data.len() {
+                return Err(load_error(format!(
+                    "the section {} is out of the bundle",
+                    name
+                )));
+            }
+
+            sections.insert(name, data.slice(start..end));
+        }
+
+        Ok(Self { header, sections })
+    }
+
+    /// Returns the section with the name.
+    pub fn section(&self, name: &str) -> LinderaResult<&DictionaryData> {
+        self.sections.get(name).ok_or_else(|| {
+            load_error(format!(
+                "the {} dictionary bundle has no {} section",
+                self.header.kind, name
+            ))
+        })
+    }
+}
+
+fn load_error<M>(message: M) -> crate::error::LinderaError
+where
+    M: std::fmt::Display + std::fmt::Debug + Send + Sync + 'static,
+{
+    LinderaErrorKind::DictionaryLoadError.with_error(anyhow::anyhow!(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    use crate::{
+        dictionary_bundle::{DictionaryBundle, BUNDLE_MAGIC},
+        dictionary_data::DictionaryData,
+        dictionary_header::write_with_header,
+        error::LinderaErrorKind,
+    };
+
+    #[test]
+    fn test_bundle() {
+        let mut buffer = Vec::new();
+        DictionaryBundle::write(
+            &mut buffer,
+            "ipadic",
+            "0.19.1",
+            &[("dict.da", b"double array"), ("dict.words", b"")],
+        )
+        .unwrap();
+
+        let bundle = DictionaryBundle::read(DictionaryData::shared(buffer.clone())).unwrap();
+        assert_eq!(bundle.header.kind, "ipadic");
+        let section = bundle.section("dict.da").unwrap();
+        assert!(section.is_borrowed());
+        assert_eq!(&section[..], b"double array");
+        assert!(bundle.section("dict.words").unwrap().is_empty());
+
+        let err = bundle.section("unk.bin").err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+
+        let mut corrupted = buffer;
+        *corrupted.last_mut().unwrap() ^= 1;
+        let err = DictionaryBundle::read(corrupted.into()).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+    }
+
+    #[test]
+    fn test_bundle_with_huge_section_count() {
+        let mut data = BUNDLE_MAGIC.to_vec();
+        data.write_u32::<LittleEndian>(u32::MAX).unwrap();
+        let mut buffer = Vec::new();
+        write_with_header(&mut buffer, "ipadic", "0.19.1", false, &data).unwrap();
+
+        let err = DictionaryBundle::read(buffer.into()).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+        assert!(err.to_string().contains("truncated"));
+    }
+}
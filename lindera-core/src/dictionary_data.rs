@@ -0,0 +1,166 @@
+use std::{
+    fmt,
+    ops::{Deref, Range},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The bytes of a dictionary.
+/// Only `Owned` holds its own copy; the others borrow data that lives elsewhere.
+#[derive(Clone)]
+pub enum DictionaryData {
+    /// Data embedded in the binary.
+    Static(&'static [u8]),
+    /// Data read into heap memory.
+    Owned(Vec<u8>),
+    /// Data shared between dictionaries, such as a memory-mapped file.
+    /// Only the bytes in the range are used.
+    Shared(Arc<dyn AsRef<[u8]> + Send + Sync>, Range<usize>),
+}
+
+impl DictionaryData {
+    /// Creates data shared with the owner, e.g. a memory-mapped file.
+    pub fn shared<T>(data: T) -> Self
+    where
+        T: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        let len = data.as_ref().len();
+        DictionaryData::Shared(Arc::new(data), 0..len)
+    }
+
+    /// Returns the bytes from the offset onwards.
+    /// Borrowed data stays borrowed.
+    pub fn slice_from(&self, offset: usize) -> Self {
+        self.slice(offset..self.len())
+    }
+
+    /// Returns the bytes in the range.
+    /// Borrowed data stays borrowed.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        match self {
+            DictionaryData::Static(data) => DictionaryData::Static(&data[range]),
+            DictionaryData::Owned(data) => DictionaryData::Owned(data[range].to_vec()),
+            DictionaryData::Shared(data, current) => {
+                // Check the bounds as slicing does.
+                let _ = &self[range.clone()];
+                DictionaryData::Shared(
+                    data.clone(),
+                    current.start + range.start..current.start + range.end,
+                )
+            }
+        }
+    }
+
+    /// Returns true if the data is not a copy of its own.
+    pub fn is_borrowed(&self) -> bool {
+        !matches!(self, DictionaryData::Owned(_))
+    }
+}
+
+impl Deref for DictionaryData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            DictionaryData::Static(data) => data,
+            DictionaryData::Owned(data) => data.as_slice(),
+            DictionaryData::Shared(data, range) => &(**data).as_ref()[range.clone()],
+        }
+    }
+}
+
+impl AsRef<[u8]> for DictionaryData {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Default for DictionaryData {
+    fn default() -> Self {
+        DictionaryData::Owned(Vec::new())
+    }
+}
+
+impl From<Vec<u8>> for DictionaryData {
+    fn from(data: Vec<u8>) -> Self {
+        DictionaryData::Owned(data)
+    }
+}
+
+impl From<&'static [u8]> for DictionaryData {
+    fn from(data: &'static [u8]) -> Self {
+        DictionaryData::Static(data)
+    }
+}
+
+impl fmt::Debug for DictionaryData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            DictionaryData::Static(_) => "Static",
+            DictionaryData::Owned(_) => "Owned",
+            DictionaryData::Shared(_, _) => "Shared",
+        };
+        write!(f, "DictionaryData::{}({} bytes)", kind, self.len())
+    }
+}
+
+// Serialized in the same format as `Vec<u8>`.
+impl Serialize for DictionaryData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DictionaryData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<u8>::deserialize(deserializer).map(DictionaryData::Owned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dictionary_data::DictionaryData;
+
+    static DATA: &[u8] = &[0, 1, 2, 3, 4];
+
+    #[test]
+    fn test_slice_from() {
+        let data = DictionaryData::Static(DATA);
+        let sliced = data.slice_from(2);
+        assert!(sliced.is_borrowed());
+        assert_eq!(&sliced[..], &[2, 3, 4]);
+        assert_eq!(sliced.as_ptr(), DATA[2..].as_ptr());
+
+        let data = DictionaryData::shared(DATA.to_vec());
+        let sliced = data.slice_from(3);
+        assert!(sliced.is_borrowed());
+        assert_eq!(&sliced[..], &[3, 4]);
+        assert_eq!(sliced.as_ptr(), data[3..].as_ptr());
+        assert_eq!(&sliced.slice_from(1)[..], &[4]);
+        assert_eq!(&data.slice(1..3)[..], &[1, 2]);
+        assert_eq!(&data.slice(1..3).slice(1..2)[..], &[2]);
+
+        let data = DictionaryData::Owned(DATA.to_vec());
+        let sliced = data.slice_from(1);
+        assert!(!sliced.is_borrowed());
+        assert_eq!(&sliced[..], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_serialize_as_vec() {
+        let data = DictionaryData::shared(DATA.to_vec()).slice_from(1);
+        let serialized = bincode::serialize(&data).unwrap();
+        assert_eq!(serialized, bincode::serialize(&DATA[1..].to_vec()).unwrap());
+
+        let deserialized: DictionaryData = bincode::deserialize(&serialized).unwrap();
+        assert!(!deserialized.is_borrowed());
+        assert_eq!(&deserialized[..], &DATA[1..]);
+    }
+}
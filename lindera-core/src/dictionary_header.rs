@@ -0,0 +1,335 @@
+use std::io::Write;
+
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
+
+use crate::{dictionary_data::DictionaryData, error::LinderaErrorKind, LinderaResult};
+
+/// The magic bytes at the beginning of every dictionary file.
+pub const MAGIC: &[u8; 8] = b"LINDERA\x1a";
+
+/// The version of the binary format of the dictionary files.
+/// Bump it whenever the layout of any dictionary file changes.
+pub const FORMAT_VERSION: u32 = 2;
+
+const COMPRESSED_FLAG: u32 = 1;
+
+// magic + format version + flags + data length + checksum
+const FIXED_LEN: usize = MAGIC.len() + 4 + 4 + 8 + 4;
+
+/// The header written in front of each file built by the dictionary builders.
+///
+/// - format_version: The version of the binary format.
+/// - compressed: Whether the data is compressed.
+/// - kind: The kind of the dictionary, e.g. `ipadic`.
+/// - builder_version: The version of the builder that built the file.
+/// - data_len: The length of the data following the header.
+/// - checksum: The CRC-32 checksum of the data following the header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryHeader {
+    pub format_version: u32,
+    pub compressed: bool,
+    pub kind: String,
+    pub builder_version: String,
+    pub data_len: u64,
+    pub checksum: u32,
+}
+
+impl DictionaryHeader {
+    /// Creates the header for the data
+    ///
+    /// # Arguments
+    ///
+    /// * `kind`: The kind of the dictionary, e.g. `ipadic`.
+    /// * `builder_version`: The version of the builder.
+    /// * `compressed`: Whether the data is compressed.
+    /// * `data`: The data following the header.
+    ///
+    /// returns: DictionaryHeader
+    ///
+    pub fn new(kind: &str, builder_version: &str, compressed: bool, data: &[u8]) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            compressed,
+            kind: kind.to_string(),
+            builder_version: builder_version.to_string(),
+            data_len: data.len() as u64,
+            checksum: crc32fast::hash(data),
+        }
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> LinderaResult<()> {
+        let flags = if self.compressed { COMPRESSED_FLAG } else { 0 };
+
+        let mut buffer = Vec::with_capacity(self.header_len());
+        buffer.extend_from_slice(MAGIC);
+        buffer
+            .write_u32::<LittleEndian>(self.format_version)
+            .and_then(|_| buffer.write_u32::<LittleEndian>(flags))
+            .and_then(|_| buffer.write_u64::<LittleEndian>(self.data_len))
+            .and_then(|_| buffer.write_u32::<LittleEndian>(self.checksum))
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+        for value in [&self.kind, &self.builder_version] {
+            buffer
+                .write_u16::<LittleEndian>(value.len() as u16)
+                .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+            buffer.extend_from_slice(value.as_bytes());
+        }
+
+        writer
+            .write_all(&buffer)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+    }
+
+    /// Returns the length of the header in bytes.
+    pub fn header_len(&self) -> usize {
+        FIXED_LEN + 2 + self.kind.len() + 2 + self.builder_version.len()
+    }
+
+    /// Parses the header at the beginning of the file.
+    /// The data following it is not checked; use `verify` for that.
+    ///
+    /// # Arguments
+    ///
+    /// * `data`: The content of the file.
+    ///
+    /// returns: Result<DictionaryHeader, LinderaError>
+    ///
+    pub fn read(data: &[u8]) -> LinderaResult<Self> {
+        if data.len() < MAGIC.len() || &data[..MAGIC.len()] != MAGIC {
+            return Err(load_error(
+                "missing Lindera dictionary header; the file was built by an older Lindera or is not a dictionary file",
+            ));
+        }
+        if data.len() < FIXED_LEN {
+            return Err(load_error("the dictionary header is truncated"));
+        }
+
+        let mut offset = MAGIC.len();
+        let format_version = LittleEndian::read_u32(&data[offset..]);
+        offset += 4;
+        if format_version != FORMAT_VERSION {
+            return Err(load_error(format!(
+                "unsupported dictionary format version {} (this Lindera supports version {}); rebuild the dictionary",
+                format_version, FORMAT_VERSION
+            )));
+        }
+        let flags = LittleEndian::read_u32(&data[offset..]);
+        offset += 4;
+        let data_len = LittleEndian::read_u64(&data[offset..]);
+        offset += 8;
+        let checksum = LittleEndian::read_u32(&data[offset..]);
+        offset += 4;
+
+        let mut strings = Vec::with_capacity(2);
+        for name in ["dictionary kind", "builder version"] {
+            if data.len() < offset + 2 {
+                return Err(load_error("the dictionary header is truncated"));
+            }
+            let len = LittleEndian::read_u16(&data[offset..]) as usize;
+            offset += 2;
+            let value = data
+                .get(offset..offset + len)
+                .ok_or_else(|| load_error("the dictionary header is truncated"))?;
+            let value = std::str::from_utf8(value)
+                .map_err(|_| load_error(format!("the {} is not valid UTF-8", name)))?;
+            strings.push(value.to_string());
+            offset += len;
+        }
+        let builder_version = strings.pop().unwrap_or_default();
+        let kind = strings.pop().unwrap_or_default();
+
+        Ok(Self {
+            format_version,
+            compressed: flags & COMPRESSED_FLAG != 0,
+            kind,
+            builder_version,
+            data_len,
+            checksum,
+        })
+    }
+
+    /// Checks the length and the checksum of the data following the header.
+    pub fn verify(&self, data: &[u8]) -> LinderaResult<()> {
+        self.verify_len(data)?;
+        let checksum = crc32fast::hash(data);
+        if checksum != self.checksum {
+            return Err(load_error(format!(
+                "checksum mismatch in the {} dictionary built by Lindera {}: expected {:08x} but found {:08x}",
+                self.kind, self.builder_version, self.checksum, checksum
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks only the length of the data following the header.
+    /// Unlike `verify`, this does not read the data.
+    pub fn verify_len(&self, data: &[u8]) -> LinderaResult<()> {
+        if data.len() as u64 != self.data_len {
+            return Err(load_error(format!(
+                "the {} dictionary built by Lindera {} is truncated or corrupted: expected {} bytes of data but found {}",
+                self.kind,
+                self.builder_version,
+                self.data_len,
+                data.len()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+fn load_error<M>(message: M) -> crate::error::LinderaError
+where
+    M: std::fmt::Display + std::fmt::Debug + Send + Sync + 'static,
+{
+    LinderaErrorKind::DictionaryLoadError.with_error(anyhow::anyhow!(message))
+}
+
+/// Writes the header followed by the data.
+///
+/// # Arguments
+///
+/// * `writer`: The destination.
+/// * `kind`: The kind of the dictionary, e.g. `ipadic`.
+/// * `builder_version`: The version of the builder.
+/// * `compressed`: Whether the data is compressed.
+/// * `data`: The data to write.
+///
+/// returns: Result<(), LinderaError>
+///
+pub fn write_with_header<W: Write>(
+    writer: &mut W,
+    kind: &str,
+    builder_version: &str,
+    compressed: bool,
+    data: &[u8],
+) -> LinderaResult<()> {
+    DictionaryHeader::new(kind, builder_version, compressed, data).write(writer)?;
+    writer
+        .write_all(data)
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+}
+
+/// Checks the header of the file and returns it with the data following it.
+pub fn read_with_header(data: &[u8]) -> LinderaResult<(DictionaryHeader, &[u8])> {
+    let header = DictionaryHeader::read(data)?;
+    let body = &data[header.header_len()..];
+    header.verify(body)?;
+
+    Ok((header, body))
+}
+
+/// Same as `read_with_header`, but keeps borrowed data, e.g. a memory-mapped file, borrowed.
+///
+/// The checksum of uncompressed borrowed data is not checked, since that would read
+/// every page of a memory-mapped file on load; only its length is.
+/// Use `read_with_header` or `DictionaryHeader::verify` to check it explicitly.
+pub fn read_data_with_header(
+    data: DictionaryData,
+) -> LinderaResult<(DictionaryHeader, DictionaryData)> {
+    let header = DictionaryHeader::read(&data)?;
+    let body = data.slice_from(header.header_len());
+    if body.is_borrowed() && !header.compressed {
+        header.verify_len(&body)?;
+    } else {
+        header.verify(&body)?;
+    }
+
+    Ok((header, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dictionary_data::DictionaryData,
+        dictionary_header::{
+            read_data_with_header, read_with_header, write_with_header, DictionaryHeader,
+            FORMAT_VERSION,
+        },
+        error::LinderaErrorKind,
+    };
+
+    fn build(data: &[u8]) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        write_with_header(&mut buffer, "ipadic", "0.19.1", false, data).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_read_with_header() {
+        let buffer = build(b"dictionary data");
+
+        let (header, body) = read_with_header(&buffer).unwrap();
+        assert_eq!(body, b"dictionary data");
+        assert_eq!(header.format_version, FORMAT_VERSION);
+        assert_eq!(header.kind, "ipadic");
+        assert_eq!(header.builder_version, "0.19.1");
+        assert!(!header.compressed);
+        assert_eq!(header.header_len() + body.len(), buffer.len());
+
+        let (header, body) = read_data_with_header(DictionaryData::shared(buffer)).unwrap();
+        assert!(body.is_borrowed());
+        assert_eq!(&body[..], b"dictionary data");
+        assert_eq!(header.kind, "ipadic");
+    }
+
+    #[test]
+    fn test_read_with_header_errors() {
+        let buffer = build(b"dictionary data");
+
+        // Data without a header.
+        let err = read_with_header(b"dictionary data").err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+
+        // Truncated header and data.
+        for len in [4, 20, buffer.len() - 1] {
+            let err = read_with_header(&buffer[..len]).err().unwrap();
+            assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+        }
+
+        // Corrupted data.
+        let mut corrupted = buffer.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        let err = read_with_header(&corrupted).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+        assert!(err.to_string().contains("checksum mismatch"));
+
+        // Another format version.
+        let mut header = DictionaryHeader::read(&buffer).unwrap();
+        header.format_version = FORMAT_VERSION + 1;
+        let mut other_version = Vec::new();
+        header.write(&mut other_version).unwrap();
+        other_version.extend_from_slice(b"dictionary data");
+        let err = read_with_header(&other_version).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+        assert!(err.to_string().contains("format version"));
+    }
+
+    #[test]
+    fn test_read_data_with_header_borrowed() {
+        let buffer = build(b"dictionary data");
+
+        // The checksum of borrowed data is only checked on request.
+        let mut corrupted = buffer.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        let (header, body) =
+            read_data_with_header(DictionaryData::shared(corrupted.clone())).unwrap();
+        let err = header.verify(&body).err().unwrap();
+        assert!(err.to_string().contains("checksum mismatch"));
+
+        // Owned data is read anyway, so its checksum is checked on load.
+        let err = read_data_with_header(DictionaryData::Owned(corrupted))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("checksum mismatch"));
+
+        // The length of borrowed data is always checked.
+        let err =
+            read_data_with_header(DictionaryData::shared(buffer[..buffer.len() - 1].to_vec()))
+                .err()
+                .unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+        assert!(err.to_string().contains("truncated"));
+    }
+}
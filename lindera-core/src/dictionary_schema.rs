@@ -0,0 +1,113 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::LinderaErrorKind, LinderaResult};
+
+/// The fields at the beginning of every row of a dictionary source,
+/// which are not part of the word details.
+pub const COMMON_FIELDS: [&str; 4] = ["surface", "left_context_id", "right_context_id", "cost"];
+
+/// The names of the fields of the rows of a dictionary source.
+/// The word details of a token are the fields following the common fields.
+///
+/// - name: The kind of the dictionary, e.g. `ipadic`.
+/// - fields: The names of all the fields, including the common fields.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DictionarySchema {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+impl DictionarySchema {
+    /// Creates the schema from the names of the fields of the word details
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The kind of the dictionary, e.g. `ipadic`.
+    /// * `detail_fields`: The names of the fields following the common fields.
+    ///
+    /// returns: DictionarySchema
+    ///
+    pub fn new(name: &str, detail_fields: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            fields: COMMON_FIELDS
+                .iter()
+                .chain(detail_fields.iter())
+                .map(|field| field.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn load(schema_data: &[u8]) -> LinderaResult<DictionarySchema> {
+        bincode::deserialize(schema_data)
+            .map_err(|err| LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err)))
+    }
+
+    /// Returns the number of the fields of a row, including the common fields.
+    pub fn field_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns the names of the fields of the word details.
+    pub fn detail_fields(&self) -> &[String] {
+        self.fields.get(COMMON_FIELDS.len()..).unwrap_or_default()
+    }
+
+    /// Returns the index of the field in the word details.
+    pub fn detail_index(&self, name: &str) -> Option<usize> {
+        self.detail_fields().iter().position(|field| field == name)
+    }
+}
+
+/// Writes the schema to `schema.bin` in the output directory.
+///
+/// # Arguments
+///
+/// * `schema`: The schema of the dictionary.
+/// * `output_dir`: The directory of the dictionary files.
+/// * `write`: Writes the serialized schema to the file, e.g. with a header and compression.
+///
+/// returns: Result<(), LinderaError>
+///
+pub fn write_schema<F>(schema: &DictionarySchema, output_dir: &Path, write: F) -> LinderaResult<()>
+where
+    F: FnOnce(&[u8], &mut io::BufWriter<File>) -> LinderaResult<()>,
+{
+    let mut schema_buffer = Vec::new();
+    bincode::serialize_into(&mut schema_buffer, schema)
+        .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+
+    let wtr_schema_path = output_dir.join(Path::new("schema.bin"));
+    let mut wtr_schema = io::BufWriter::new(
+        File::create(wtr_schema_path)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
+    );
+    write(&schema_buffer, &mut wtr_schema)?;
+    wtr_schema
+        .flush()
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dictionary_schema::DictionarySchema;
+
+    #[test]
+    fn test_dictionary_schema() {
+        let schema = DictionarySchema::new("ipadic", &["pos", "reading"]);
+        assert_eq!(schema.field_count(), 6);
+        assert_eq!(schema.fields[0], "surface");
+        assert_eq!(schema.detail_fields(), &["pos", "reading"]);
+        assert_eq!(schema.detail_index("reading"), Some(1));
+        assert_eq!(schema.detail_index("cost"), None);
+
+        let data = bincode::serialize(&schema).unwrap();
+        assert_eq!(DictionarySchema::load(&data).unwrap(), schema);
+    }
+}
@@ -33,7 +33,7 @@ impl LinderaErrorKind {
 }
 
 #[derive(thiserror::Error, Debug)]
-#[error("LinderaError(kind={kind:?}, source={source})")]
+#[error("LinderaError(kind={kind:?}, source={source:#})")]
 pub struct LinderaError {
     pub kind: LinderaErrorKind,
     #[source]
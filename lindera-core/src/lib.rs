@@ -3,10 +3,16 @@ pub mod character_filter;
 pub mod connection;
 pub mod dictionary;
 pub mod dictionary_builder;
+pub mod dictionary_bundle;
+pub mod dictionary_data;
+pub mod dictionary_header;
+pub mod dictionary_schema;
 pub mod error;
 pub mod file_util;
 pub mod prefix_dict;
+pub mod sentence_splitter;
 pub mod token;
+pub mod token_features;
 pub mod token_filter;
 pub mod unknown_dictionary;
 pub mod user_dictionary;
@@ -3,7 +3,7 @@ use std::ops::Deref;
 use serde::{Deserialize, Serialize};
 use yada::DoubleArray;
 
-use crate::word_entry::WordEntry;
+use crate::{dictionary_data::DictionaryData, word_entry::WordEntry};
 
 #[derive(Serialize, Deserialize)]
 #[serde(remote = "DoubleArray")]
@@ -11,22 +11,33 @@ struct DoubleArrayDef<T>(pub T)
 where
     T: Deref<Target = [u8]>;
 
+/// The data is either owned or borrowed, e.g. from a dictionary embedded in the binary.
 #[derive(Clone, Serialize, Deserialize)]
-pub struct PrefixDict<Data = Vec<u8>> {
+pub struct PrefixDict<Data = DictionaryData>
+where
+    Data: Deref<Target = [u8]>,
+{
     #[serde(with = "DoubleArrayDef")]
-    pub da: DoubleArray<Vec<u8>>,
+    pub da: DoubleArray<Data>,
 
     pub vals_data: Data,
     pub is_system: bool,
 }
 
-impl PrefixDict<&[u8]> {
-    pub fn from_static_slice(da_data: &[u8], vals_data: &[u8]) -> PrefixDict {
-        let da = DoubleArray::new(da_data.to_vec());
+impl PrefixDict {
+    /// Borrows the data without copying it.
+    pub fn from_static_slice(da_data: &'static [u8], vals_data: &'static [u8]) -> PrefixDict {
+        PrefixDict {
+            da: DoubleArray::new(DictionaryData::Static(da_data)),
+            vals_data: DictionaryData::Static(vals_data),
+            is_system: true,
+        }
+    }
 
+    pub fn from_data(da_data: DictionaryData, vals_data: DictionaryData) -> PrefixDict {
         PrefixDict {
-            da,
-            vals_data: vals_data.to_vec(),
+            da: DoubleArray::new(da_data),
+            vals_data,
             is_system: true,
         }
     }
@@ -0,0 +1,25 @@
+use std::ops::Range;
+
+pub trait SentenceSplitter: 'static + Send + Sync + SentenceSplitterClone {
+    fn name(&self) -> &str;
+
+    /// Returns the byte ranges of the sentences in the text.
+    /// The ranges are in order and cover the whole text without gaps.
+    fn split(&self, text: &str) -> Vec<Range<usize>>;
+}
+
+pub trait SentenceSplitterClone {
+    fn box_clone(&self) -> Box<dyn SentenceSplitter>;
+}
+
+impl<T: SentenceSplitter + Clone + 'static> SentenceSplitterClone for T {
+    fn box_clone(&self) -> Box<dyn SentenceSplitter> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn SentenceSplitter> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
@@ -1,11 +1,215 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::Arc};
 
 use serde::Serialize;
 
-#[derive(Serialize, Clone)]
+use crate::{
+    dictionary::DictionaryKind,
+    dictionary_schema::DictionarySchema,
+    token_features::TokenFeatures,
+    viterbi::{Edge, EdgeType},
+    word_entry::WordId,
+};
+
+/// What the lattice knows about a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TokenMetadata {
+    pub word_id: WordId,
+    /// Where the token came from: the system dictionary, the user dictionary or unknown word processing.
+    pub edge_type: EdgeType,
+    pub word_cost: i32,
+    pub left_context_id: u32,
+    pub right_context_id: u32,
+    /// The cost of the best path from the beginning of the sentence up to and including the token.
+    pub path_cost: i32,
+}
+
+impl TokenMetadata {
+    pub fn from_edge(edge: &Edge) -> Self {
+        Self {
+            word_id: edge.word_entry.word_id,
+            edge_type: edge.edge_type,
+            word_cost: edge.word_entry.word_cost as i32,
+            left_context_id: edge.word_entry.left_id(),
+            right_context_id: edge.word_entry.right_id(),
+            path_cost: edge.path_cost,
+        }
+    }
+
+    /// Returns whether the token is an unknown word, including the words inserted for constraints.
+    pub fn is_unknown(&self) -> bool {
+        matches!(self.edge_type, EdgeType::UNKNOWN | EdgeType::INSERTED)
+    }
+
+    /// Returns whether the token came from the user dictionary.
+    pub fn is_user(&self) -> bool {
+        self.edge_type == EdgeType::USER
+    }
+}
+
+#[derive(Serialize, Clone, Default)]
 pub struct Token<'a> {
     pub text: Cow<'a, str>,
     pub details: Option<Vec<String>>,
     pub byte_start: usize,
     pub byte_end: usize,
+    pub confidence: Option<f64>,
+    /// The kind of the dictionary the token came from, which tells the layout of the details.
+    #[serde(skip)]
+    pub dictionary_kind: Option<DictionaryKind>,
+    /// The schema of the dictionary the token came from, which names the details.
+    #[serde(skip)]
+    pub schema: Option<Arc<DictionarySchema>>,
+    /// What the lattice knows about the token.
+    /// `None` if the token was made by a token filter, e.g. a compound word.
+    pub metadata: Option<TokenMetadata>,
+    /// Character offsets of the token in the text.
+    pub char_start: Option<usize>,
+    pub char_end: Option<usize>,
+    /// UTF-16 code unit offsets of the token in the text, as used by Java and JavaScript.
+    pub utf16_start: Option<usize>,
+    pub utf16_end: Option<usize>,
+    /// The position of the token in the token stream.
+    /// The positions of the tokens removed by token filters are left as gaps.
+    pub position: Option<usize>,
+    /// The number of positions the token spans, e.g. more than 1 for a compound word
+    /// that spans the positions of its parts.
+    pub position_length: Option<usize>,
+}
+
+impl<'a> Token<'a> {
+    /// Returns the details as the typed features of the dictionary.
+    /// `None` if the token has no details, or came from an unknown kind of dictionary.
+    pub fn features(&self) -> Option<TokenFeatures<'_>> {
+        let kind = self.dictionary_kind.as_ref()?;
+        let details = self.details.as_ref()?;
+
+        Some(TokenFeatures::new(kind, details))
+    }
+
+    /// Returns whether the token is an unknown word.
+    /// `false` if the token has no metadata.
+    pub fn is_unknown(&self) -> bool {
+        matches!(self.metadata, Some(metadata) if metadata.is_unknown())
+    }
+
+    /// Returns the major part-of-speech.
+    pub fn pos(&self) -> Option<&str> {
+        self.features().and_then(|features| features.pos())
+    }
+
+    /// Returns the reading.
+    pub fn reading(&self) -> Option<&str> {
+        self.features().and_then(|features| features.reading())
+    }
+
+    /// Returns the base form. Only the Japanese dictionaries have one.
+    pub fn base_form(&self) -> Option<&str> {
+        self.features().and_then(|features| features.base_form())
+    }
+
+    /// Returns the detail with the name in the schema of the dictionary, e.g. `reading`.
+    /// `None` if the token has no details or the dictionary has no such field.
+    pub fn get_detail(&self, name: &str) -> Option<&str> {
+        let index = self.schema.as_ref()?.detail_index(name)?;
+        self.details
+            .as_ref()?
+            .get(index)
+            .map(|detail| detail.as_str())
+    }
+
+    /// Returns the details paired with their names in the schema of the dictionary.
+    /// `None` if the token has no details or no schema.
+    pub fn named_details(&self) -> Option<Vec<(&str, &str)>> {
+        let schema = self.schema.as_ref()?;
+        let details = self.details.as_ref()?;
+
+        Some(
+            schema
+                .detail_fields()
+                .iter()
+                .zip(details.iter())
+                .map(|(name, detail)| (name.as_str(), detail.as_str()))
+                .collect(),
+        )
+    }
+}
+
+/// Sets the character and UTF-16 offsets of the tokens from their byte offsets in the text.
+/// A byte offset inside a character is counted as the end of that character.
+///
+/// # Arguments
+///
+/// * `text`: The text the byte offsets of the tokens refer to.
+/// * `tokens`: The tokens, usually ordered by their byte offsets.
+///
+pub fn set_char_offsets(text: &str, tokens: &mut [Token]) {
+    // Moves the (byte offset, char offset, UTF-16 offset) cursor forward to the byte offset.
+    let advance = |cursor: &mut (usize, usize, usize), byte_offset: usize| {
+        for c in text[cursor.0..].chars() {
+            if cursor.0 >= byte_offset {
+                break;
+            }
+            *cursor = (
+                cursor.0 + c.len_utf8(),
+                cursor.1 + 1,
+                cursor.2 + c.len_utf16(),
+            );
+        }
+    };
+
+    // The cursor at the start of the previous token. Tokens start in order, except that the
+    // parts of a compound word start again at the start of the compound word, so only the
+    // text between consecutive starts and inside each token is scanned.
+    let mut start_cursor = (0, 0, 0);
+    for token in tokens.iter_mut() {
+        if token.byte_start < start_cursor.0 {
+            start_cursor = (0, 0, 0);
+        }
+        advance(&mut start_cursor, token.byte_start);
+        let mut end_cursor = start_cursor;
+        advance(&mut end_cursor, token.byte_end);
+
+        token.char_start = Some(start_cursor.1);
+        token.char_end = Some(end_cursor.1);
+        token.utf16_start = Some(start_cursor.2);
+        token.utf16_end = Some(end_cursor.2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::token::{set_char_offsets, Token};
+
+    fn token(text: &str, byte_start: usize, byte_end: usize) -> Token<'_> {
+        Token {
+            text: Cow::Borrowed(text),
+            details: None,
+            byte_start,
+            byte_end,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_set_char_offsets() {
+        // "😀" is 4 bytes, 1 character and 2 UTF-16 code units.
+        let text = "a😀東京";
+        let mut tokens = vec![token("a😀", 0, 5), token("東京", 5, 11), token("東", 5, 8)];
+        set_char_offsets(text, &mut tokens);
+
+        let offsets: Vec<_> = tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.char_start.unwrap(),
+                    token.char_end.unwrap(),
+                    token.utf16_start.unwrap(),
+                    token.utf16_end.unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(offsets, vec![(0, 2, 0, 3), (2, 4, 3, 5), (2, 3, 3, 4)]);
+    }
 }
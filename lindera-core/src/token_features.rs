@@ -0,0 +1,350 @@
+use crate::dictionary::DictionaryKind;
+
+// Not applicable fields are filled with `*` by the dictionaries.
+fn field(details: &[String], index: usize) -> Option<&str> {
+    details
+        .get(index)
+        .map(|value| value.as_str())
+        .filter(|value| *value != "*" && !value.is_empty())
+}
+
+/// The features of a token in IPADIC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpadicFeatures<'a> {
+    pub pos: Option<&'a str>,
+    pub pos1: Option<&'a str>,
+    pub pos2: Option<&'a str>,
+    pub pos3: Option<&'a str>,
+    pub conjugation_type: Option<&'a str>,
+    pub conjugation_form: Option<&'a str>,
+    pub base_form: Option<&'a str>,
+    pub reading: Option<&'a str>,
+    pub pronunciation: Option<&'a str>,
+}
+
+impl<'a> IpadicFeatures<'a> {
+    /// The names of the fields of the word details, in the order of the dictionary.
+    pub const FIELDS: [&'static str; 9] = [
+        "pos",
+        "pos1",
+        "pos2",
+        "pos3",
+        "conjugation_type",
+        "conjugation_form",
+        "base_form",
+        "reading",
+        "pronunciation",
+    ];
+
+    pub fn from_details(details: &'a [String]) -> Self {
+        Self {
+            pos: field(details, 0),
+            pos1: field(details, 1),
+            pos2: field(details, 2),
+            pos3: field(details, 3),
+            conjugation_type: field(details, 4),
+            conjugation_form: field(details, 5),
+            base_form: field(details, 6),
+            reading: field(details, 7),
+            pronunciation: field(details, 8),
+        }
+    }
+}
+
+/// The features of a token in UniDic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnidicFeatures<'a> {
+    pub pos: Option<&'a str>,
+    pub pos1: Option<&'a str>,
+    pub pos2: Option<&'a str>,
+    pub pos3: Option<&'a str>,
+    pub conjugation_type: Option<&'a str>,
+    pub conjugation_form: Option<&'a str>,
+    pub lexeme_reading: Option<&'a str>,
+    pub lexeme: Option<&'a str>,
+    pub orthography: Option<&'a str>,
+    pub pronunciation: Option<&'a str>,
+    pub orthography_base_form: Option<&'a str>,
+    pub pronunciation_base_form: Option<&'a str>,
+    pub word_type: Option<&'a str>,
+    pub initial_mutation_type: Option<&'a str>,
+    pub initial_mutation_form: Option<&'a str>,
+    pub final_mutation_type: Option<&'a str>,
+    pub final_mutation_form: Option<&'a str>,
+}
+
+impl<'a> UnidicFeatures<'a> {
+    /// The names of the fields of the word details, in the order of the dictionary.
+    pub const FIELDS: [&'static str; 17] = [
+        "pos",
+        "pos1",
+        "pos2",
+        "pos3",
+        "conjugation_type",
+        "conjugation_form",
+        "lexeme_reading",
+        "lexeme",
+        "orthography",
+        "pronunciation",
+        "orthography_base_form",
+        "pronunciation_base_form",
+        "word_type",
+        "initial_mutation_type",
+        "initial_mutation_form",
+        "final_mutation_type",
+        "final_mutation_form",
+    ];
+
+    pub fn from_details(details: &'a [String]) -> Self {
+        Self {
+            pos: field(details, 0),
+            pos1: field(details, 1),
+            pos2: field(details, 2),
+            pos3: field(details, 3),
+            conjugation_type: field(details, 4),
+            conjugation_form: field(details, 5),
+            lexeme_reading: field(details, 6),
+            lexeme: field(details, 7),
+            orthography: field(details, 8),
+            pronunciation: field(details, 9),
+            orthography_base_form: field(details, 10),
+            pronunciation_base_form: field(details, 11),
+            word_type: field(details, 12),
+            initial_mutation_type: field(details, 13),
+            initial_mutation_form: field(details, 14),
+            final_mutation_type: field(details, 15),
+            final_mutation_form: field(details, 16),
+        }
+    }
+}
+
+/// The features of a token in ko-dic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KoDicFeatures<'a> {
+    pub pos_tag: Option<&'a str>,
+    pub meaning: Option<&'a str>,
+    pub final_consonant: Option<&'a str>,
+    pub reading: Option<&'a str>,
+    pub word_type: Option<&'a str>,
+    pub first_pos: Option<&'a str>,
+    pub last_pos: Option<&'a str>,
+    pub expression: Option<&'a str>,
+}
+
+impl<'a> KoDicFeatures<'a> {
+    /// The names of the fields of the word details, in the order of the dictionary.
+    pub const FIELDS: [&'static str; 8] = [
+        "pos_tag",
+        "meaning",
+        "final_consonant",
+        "reading",
+        "word_type",
+        "first_pos",
+        "last_pos",
+        "expression",
+    ];
+
+    pub fn from_details(details: &'a [String]) -> Self {
+        Self {
+            pos_tag: field(details, 0),
+            meaning: field(details, 1),
+            final_consonant: field(details, 2),
+            reading: field(details, 3),
+            word_type: field(details, 4),
+            first_pos: field(details, 5),
+            last_pos: field(details, 6),
+            expression: field(details, 7),
+        }
+    }
+}
+
+/// The features of a token in CC-CEDICT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CcCedictFeatures<'a> {
+    pub pos: Option<&'a str>,
+    pub pos1: Option<&'a str>,
+    pub pos2: Option<&'a str>,
+    pub pos3: Option<&'a str>,
+    pub pinyin: Option<&'a str>,
+    pub traditional: Option<&'a str>,
+    pub simplified: Option<&'a str>,
+    pub definition: Option<&'a str>,
+}
+
+impl<'a> CcCedictFeatures<'a> {
+    /// The names of the fields of the word details, in the order of the dictionary.
+    pub const FIELDS: [&'static str; 8] = [
+        "pos",
+        "pos1",
+        "pos2",
+        "pos3",
+        "pinyin",
+        "traditional",
+        "simplified",
+        "definition",
+    ];
+
+    pub fn from_details(details: &'a [String]) -> Self {
+        Self {
+            pos: field(details, 0),
+            pos1: field(details, 1),
+            pos2: field(details, 2),
+            pos3: field(details, 3),
+            pinyin: field(details, 4),
+            traditional: field(details, 5),
+            simplified: field(details, 6),
+            definition: field(details, 7),
+        }
+    }
+}
+
+/// The features of a token, typed by the dictionary it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenFeatures<'a> {
+    Ipadic(IpadicFeatures<'a>),
+    Unidic(UnidicFeatures<'a>),
+    KoDic(KoDicFeatures<'a>),
+    CcCedict(CcCedictFeatures<'a>),
+}
+
+impl<'a> TokenFeatures<'a> {
+    /// Reads the details of a token as the features of the dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind`: The kind of the dictionary the token came from.
+    /// * `details`: The details of the token.
+    ///
+    /// returns: TokenFeatures
+    ///
+    pub fn new(kind: &DictionaryKind, details: &'a [String]) -> Self {
+        match kind {
+            DictionaryKind::IPADIC => TokenFeatures::Ipadic(IpadicFeatures::from_details(details)),
+            DictionaryKind::UniDic => TokenFeatures::Unidic(UnidicFeatures::from_details(details)),
+            DictionaryKind::KoDic => TokenFeatures::KoDic(KoDicFeatures::from_details(details)),
+            DictionaryKind::CcCedict => {
+                TokenFeatures::CcCedict(CcCedictFeatures::from_details(details))
+            }
+        }
+    }
+
+    /// Returns the major part-of-speech.
+    pub fn pos(&self) -> Option<&'a str> {
+        match self {
+            TokenFeatures::Ipadic(features) => features.pos,
+            TokenFeatures::Unidic(features) => features.pos,
+            TokenFeatures::KoDic(features) => features.pos_tag,
+            TokenFeatures::CcCedict(features) => features.pos,
+        }
+    }
+
+    /// Returns the reading, i.e. katakana for Japanese, hangul for Korean and pinyin for Chinese.
+    pub fn reading(&self) -> Option<&'a str> {
+        match self {
+            TokenFeatures::Ipadic(features) => features.reading,
+            TokenFeatures::Unidic(features) => features.lexeme_reading,
+            TokenFeatures::KoDic(features) => features.reading,
+            TokenFeatures::CcCedict(features) => features.pinyin,
+        }
+    }
+
+    /// Returns the base form. Only the Japanese dictionaries have one.
+    pub fn base_form(&self) -> Option<&'a str> {
+        match self {
+            TokenFeatures::Ipadic(features) => features.base_form,
+            TokenFeatures::Unidic(features) => features.orthography_base_form,
+            TokenFeatures::KoDic(_) | TokenFeatures::CcCedict(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dictionary::DictionaryKind,
+        token_features::{
+            CcCedictFeatures, IpadicFeatures, KoDicFeatures, TokenFeatures, UnidicFeatures,
+        },
+    };
+
+    fn details(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_token_features() {
+        let ipadic = details(&[
+            "名詞",
+            "固有名詞",
+            "地域",
+            "一般",
+            "*",
+            "*",
+            "東京",
+            "トウキョウ",
+            "トーキョー",
+        ]);
+        let features = TokenFeatures::new(&DictionaryKind::IPADIC, &ipadic);
+        assert_eq!(features.pos(), Some("名詞"));
+        assert_eq!(features.reading(), Some("トウキョウ"));
+        assert_eq!(features.base_form(), Some("東京"));
+        match features {
+            TokenFeatures::Ipadic(IpadicFeatures {
+                pos2,
+                conjugation_type,
+                ..
+            }) => {
+                assert_eq!(pos2, Some("地域"));
+                assert_eq!(conjugation_type, None);
+            }
+            _ => panic!("unexpected features: {:?}", features),
+        }
+
+        let ko_dic = details(&["NNG", "*", "F", "한국어", "Compound", "*", "*", "*"]);
+        let features = TokenFeatures::new(&DictionaryKind::KoDic, &ko_dic);
+        assert_eq!(features.pos(), Some("NNG"));
+        assert_eq!(features.reading(), Some("한국어"));
+        assert_eq!(features.base_form(), None);
+
+        let cc_cedict = details(&["*", "*", "*", "*", "zhong1 guo2", "中國", "中国", "China/"]);
+        let features = TokenFeatures::new(&DictionaryKind::CcCedict, &cc_cedict);
+        assert_eq!(features.pos(), None);
+        assert_eq!(features.reading(), Some("zhong1 guo2"));
+
+        // Missing fields, e.g. in the details of a user dictionary.
+        let short = details(&["名詞"]);
+        let features = TokenFeatures::new(&DictionaryKind::UniDic, &short);
+        assert_eq!(features.pos(), Some("名詞"));
+        assert_eq!(features.reading(), None);
+    }
+
+    #[test]
+    fn test_fields() {
+        // Each field is read from the position of its name.
+        let ipadic = details(&IpadicFeatures::FIELDS);
+        let features = IpadicFeatures::from_details(&ipadic);
+        assert_eq!(features.pos, Some("pos"));
+        assert_eq!(features.base_form, Some("base_form"));
+        assert_eq!(features.pronunciation, Some("pronunciation"));
+
+        let unidic = details(&UnidicFeatures::FIELDS);
+        let features = UnidicFeatures::from_details(&unidic);
+        assert_eq!(features.lexeme_reading, Some("lexeme_reading"));
+        assert_eq!(
+            features.orthography_base_form,
+            Some("orthography_base_form")
+        );
+        assert_eq!(features.final_mutation_form, Some("final_mutation_form"));
+
+        let ko_dic = details(&KoDicFeatures::FIELDS);
+        let features = KoDicFeatures::from_details(&ko_dic);
+        assert_eq!(features.pos_tag, Some("pos_tag"));
+        assert_eq!(features.reading, Some("reading"));
+        assert_eq!(features.expression, Some("expression"));
+
+        let cc_cedict = details(&CcCedictFeatures::FIELDS);
+        let features = CcCedictFeatures::from_details(&cc_cedict);
+        assert_eq!(features.pinyin, Some("pinyin"));
+        assert_eq!(features.definition, Some("definition"));
+    }
+}
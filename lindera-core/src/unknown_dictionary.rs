@@ -1,6 +1,5 @@
 use std::{str::FromStr, u32};
 
-use log::warn;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -14,6 +13,8 @@ use crate::{
 pub struct UnknownDictionary {
     pub category_references: Vec<Vec<u32>>,
     pub costs: Vec<WordEntry>,
+    /// The features of the entries, laid out as the details of the dictionary words.
+    pub details: Vec<Vec<String>>,
 }
 
 impl UnknownDictionary {
@@ -26,6 +27,14 @@ impl UnknownDictionary {
         self.costs[word_id as usize]
     }
 
+    /// Returns the details of the entry of the unknown word.
+    pub fn word_details(&self, word_id: WordId) -> Option<&[String]> {
+        let index = word_id.unknown_index()?;
+        self.details
+            .get(index as usize)
+            .map(|details| details.as_slice())
+    }
+
     pub fn lookup_word_ids(&self, category_id: CategoryId) -> &[u32] {
         &self.category_references[category_id.0][..]
     }
@@ -37,11 +46,13 @@ pub struct UnknownDictionaryEntry {
     pub left_id: u32,
     pub right_id: u32,
     pub word_cost: i32,
+    pub details: Vec<String>,
 }
 
 fn parse_dictionary_entry(
     fields: &[&str],
     expected_fields_len: usize,
+    details_len: usize,
 ) -> LinderaResult<UnknownDictionaryEntry> {
     if fields.len() != expected_fields_len {
         return Err(LinderaErrorKind::Content.with_error(anyhow::anyhow!(
@@ -57,12 +68,18 @@ fn parse_dictionary_entry(
         .map_err(|err| LinderaErrorKind::Parse.with_error(anyhow::anyhow!(err)))?;
     let word_cost = i32::from_str(fields[3])
         .map_err(|err| LinderaErrorKind::Parse.with_error(anyhow::anyhow!(err)))?;
+    // Fills the fields missing in the unknown dictionary, e.g. the reading, with `*`.
+    let mut details: Vec<String> = fields[4..].iter().map(|field| field.to_string()).collect();
+    if details.len() < details_len {
+        details.resize(details_len, "*".to_string());
+    }
 
     Ok(UnknownDictionaryEntry {
         surface: surface.to_string(),
         left_id,
         right_id,
         word_cost,
+        details,
     })
 }
 
@@ -96,40 +113,76 @@ fn make_category_references(
 fn make_costs_array(entries: &[UnknownDictionaryEntry]) -> Vec<WordEntry> {
     entries
         .iter()
-        .map(|e| {
-            // Do not perform strict checks on left context id and right context id in unk.def.
-            // Just output a warning.
-            if e.left_id != e.right_id {
-                warn!("left id and right id are not same: {:?}", e);
-            }
-            WordEntry {
-                word_id: WordId(std::u32::MAX, true),
-                cost_id: e.left_id as u16,
-                word_cost: e.word_cost as i16,
-            }
+        .enumerate()
+        .map(|(entry_id, e)| WordEntry {
+            word_id: WordId::unknown(entry_id as u32),
+            word_cost: e.word_cost as i16,
+            left_id: e.left_id as u16,
+            right_id: e.right_id as u16,
         })
         .collect()
 }
 
+/// Parses the content of unk.def.
+///
+/// # Arguments
+///
+/// * `categories`: The names of the character categories.
+/// * `file_content`: The content of unk.def.
+/// * `expected_fields_len`: The number of the fields of a row of unk.def.
+/// * `details_len`: The number of the fields of the details of the dictionary words.
+///
+/// returns: Result<UnknownDictionary, LinderaError>
+///
 pub fn parse_unk(
     categories: &[String],
     file_content: &str,
     expected_fields_len: usize,
+    details_len: usize,
 ) -> LinderaResult<UnknownDictionary> {
     let mut unknown_dict_entries = Vec::new();
     for line in file_content.lines() {
         let fields: Vec<&str> = line.split(',').collect::<Vec<&str>>();
-        let entry = parse_dictionary_entry(&fields[..], expected_fields_len)?;
+        let entry = parse_dictionary_entry(&fields[..], expected_fields_len, details_len)?;
         unknown_dict_entries.push(entry);
     }
 
     let category_references = make_category_references(categories, &unknown_dict_entries[..]);
     let costs = make_costs_array(&unknown_dict_entries[..]);
+    let details = unknown_dict_entries
+        .into_iter()
+        .map(|entry| entry.details)
+        .collect();
     Ok(UnknownDictionary {
         category_references,
         costs,
+        details,
     })
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use crate::{character_definition::CategoryId, unknown_dictionary::parse_unk};
+
+    #[test]
+    fn test_parse_unk() {
+        let categories = vec!["DEFAULT".to_string(), "NUMERIC".to_string()];
+        let unknown_dictionary = parse_unk(
+            &categories,
+            "DEFAULT,5,5,4769,記号,一般,*,*,*,*,*\nNUMERIC,1295,1295,27473,名詞,数,*,*,*,*,*",
+            11,
+            9,
+        )
+        .unwrap();
+
+        let word_ids = unknown_dictionary.lookup_word_ids(CategoryId(1));
+        assert_eq!(word_ids, &[1]);
+        let word_entry = unknown_dictionary.word_entry(word_ids[0]);
+        assert!(word_entry.word_id.is_unknown());
+        assert_eq!(word_entry.word_cost, 27473);
+        assert_eq!(
+            unknown_dictionary.word_details(word_entry.word_id).unwrap(),
+            &["名詞", "数", "*", "*", "*", "*", "*", "*", "*"]
+        );
+    }
+}
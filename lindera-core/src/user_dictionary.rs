@@ -1,16 +1,20 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{error::LinderaErrorKind, prefix_dict::PrefixDict, LinderaResult};
+use crate::{
+    dictionary_header::read_with_header, error::LinderaErrorKind, prefix_dict::PrefixDict,
+    LinderaResult,
+};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct UserDictionary {
-    pub dict: PrefixDict<Vec<u8>>,
+    pub dict: PrefixDict,
     pub words_idx_data: Vec<u8>,
     pub words_data: Vec<u8>,
 }
 
 impl UserDictionary {
     pub fn load(user_dict_data: &[u8]) -> LinderaResult<UserDictionary> {
+        let (_header, user_dict_data) = read_with_header(user_dict_data)?;
         bincode::deserialize(user_dict_data)
             .map_err(|err| LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err)))
     }
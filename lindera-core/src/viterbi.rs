@@ -1,9 +1,16 @@
-use std::{str::FromStr, u32};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::Write,
+    str::FromStr,
+    u32,
+};
 
 use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
-    character_definition::{CategoryId, CharacterDefinitions},
+    character_definition::CharacterDefinitions,
     connection::ConnectionCostMatrix,
     error::{LinderaError, LinderaErrorKind},
     prefix_dict::PrefixDict,
@@ -11,12 +18,24 @@ use crate::{
     word_entry::{WordEntry, WordId},
 };
 
+/// A length penalty for words whose characters all belong to a char.def category.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CategoryPenalty {
+    /// The char.def category name, such as KANJI, KATAKANA or HANGUL.
+    pub category: String,
+    pub length_threshold: usize,
+    pub length_penalty: i32,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Penalty {
     pub kanji_penalty_length_threshold: usize,
     pub kanji_penalty_length_penalty: i32,
     pub other_penalty_length_threshold: usize,
     pub other_penalty_length_penalty: i32,
+    /// Penalties keyed by char.def category, checked in order before the KANJI penalty.
+    #[serde(default)]
+    pub category_penalties: Vec<CategoryPenalty>,
 }
 
 impl Default for Penalty {
@@ -26,22 +45,62 @@ impl Default for Penalty {
             kanji_penalty_length_penalty: 3000,
             other_penalty_length_threshold: 7,
             other_penalty_length_penalty: 1700,
+            category_penalties: Vec::new(),
         }
     }
 }
 
 impl Penalty {
-    pub fn penalty(&self, edge: &Edge) -> i32 {
-        let num_chars = edge.num_chars();
-        if num_chars <= self.kanji_penalty_length_threshold {
-            return 0;
-        }
-        if edge.kanji_only {
-            ((num_chars - self.kanji_penalty_length_threshold) as i32)
-                * self.kanji_penalty_length_penalty
-        } else if num_chars > self.other_penalty_length_threshold {
-            ((num_chars - self.other_penalty_length_threshold) as i32)
-                * self.other_penalty_length_penalty
+    /// Returns the penalty of a word, charged per character beyond the length threshold.
+    ///
+    /// The threshold and the per-character cost come from the first category penalty whose
+    /// category every character belongs to, then from the KANJI penalty, and otherwise from
+    /// the penalty for other words.
+    pub fn penalty(&self, surface: &str, char_definitions: &CharacterDefinitions) -> i32 {
+        let num_chars = surface.chars().count();
+
+        // Whether all the characters of the word belong to the category.
+        // Only the categories of the first character are candidates.
+        let first_categories = match surface.chars().next() {
+            Some(c) => char_definitions.lookup_categories(c),
+            None => return 0,
+        };
+        let belongs_to = |category_name: &str| {
+            first_categories.iter().any(|category| {
+                char_definitions.category_name(*category) == category_name
+                    && surface
+                        .chars()
+                        .skip(1)
+                        .all(|c| char_definitions.lookup_categories(c).contains(category))
+            })
+        };
+
+        let (threshold, penalty) = self
+            .category_penalties
+            .iter()
+            .find(|category_penalty| belongs_to(&category_penalty.category))
+            .map(|category_penalty| {
+                (
+                    category_penalty.length_threshold,
+                    category_penalty.length_penalty,
+                )
+            })
+            .unwrap_or_else(|| {
+                if belongs_to(KANJI_CATEGORY_NAME) {
+                    (
+                        self.kanji_penalty_length_threshold,
+                        self.kanji_penalty_length_penalty,
+                    )
+                } else {
+                    (
+                        self.other_penalty_length_threshold,
+                        self.other_penalty_length_penalty,
+                    )
+                }
+            });
+
+        if num_chars > threshold {
+            ((num_chars - threshold) as i32) * penalty
         } else {
             0
         }
@@ -54,19 +113,43 @@ pub enum Mode {
     Normal,
     #[serde(rename = "decompose")]
     Decompose(Penalty),
+    /// Emits both the words of the normal mode and their parts in the decompose mode,
+    /// like the search mode of Lucene Kuromoji.
+    /// The lattice itself is built with the penalties of the decompose mode.
+    #[serde(rename = "search")]
+    Search(Penalty),
+    /// Same as the decompose mode, but unknown words are split into unigrams of grapheme clusters,
+    /// like the extended mode of Lucene Kuromoji.
+    #[serde(rename = "extended")]
+    Extended(Penalty),
 }
 
 impl Mode {
     pub fn is_search(&self) -> bool {
         match self {
             Mode::Normal => false,
-            Mode::Decompose(_penalty) => true,
+            Mode::Decompose(_penalty) | Mode::Search(_penalty) | Mode::Extended(_penalty) => true,
         }
     }
+    /// Returns the penalty charged when connecting the edge to the next one.
+    /// The penalty of the edge is computed when it is added to the lattice, so it is only
+    /// charged if this mode has penalties.
     pub fn penalty_cost(&self, edge: &Edge) -> i32 {
         match self {
             Mode::Normal => 0i32,
-            Mode::Decompose(penalty) => penalty.penalty(edge),
+            Mode::Decompose(_penalty) | Mode::Search(_penalty) | Mode::Extended(_penalty) => {
+                edge.penalty_cost
+            }
+        }
+    }
+
+    /// Returns the penalty of a word with the surface.
+    pub fn word_penalty_cost(&self, surface: &str, char_definitions: &CharacterDefinitions) -> i32 {
+        match self {
+            Mode::Normal => 0i32,
+            Mode::Decompose(penalty) | Mode::Search(penalty) | Mode::Extended(penalty) => {
+                penalty.penalty(surface, char_definitions)
+            }
         }
     }
 }
@@ -77,6 +160,8 @@ impl FromStr for Mode {
         match mode {
             "normal" => Ok(Mode::Normal),
             "decompose" => Ok(Mode::Decompose(Penalty::default())),
+            "search" => Ok(Mode::Search(Penalty::default())),
+            "extended" => Ok(Mode::Extended(Penalty::default())),
             _ => {
                 Err(LinderaErrorKind::ModeError
                     .with_error(anyhow::anyhow!("Invalid mode: {}", mode)))
@@ -85,9 +170,15 @@ impl FromStr for Mode {
     }
 }
 
+/// The maximum number of characters grouped into an unknown word, the same as MeCab.
+const MAX_GROUPING_SIZE: usize = 24;
+
+const KANJI_CATEGORY_NAME: &str = "KANJI";
+
+const BOS_NODE: EdgeId = EdgeId(0u32);
 const EOS_NODE: EdgeId = EdgeId(1u32);
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum EdgeType {
     KNOWN,
     UNKNOWN,
@@ -115,13 +206,157 @@ pub struct Edge {
     pub start_index: u32,
     pub stop_index: u32,
 
-    pub kanji_only: bool,
+    /// The penalty of the tokenize mode, charged when connecting the edge to the next one.
+    pub penalty_cost: i32,
+}
+
+/// A snapshot of a lattice edge and its costs, for debugging segmentations.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EdgeDump {
+    pub id: u32,
+    /// The surface of the edge, or "BOS" / "EOS" for the sentinel edges.
+    pub surface: String,
+    /// Byte offsets of the edge in the text.
+    pub start: usize,
+    pub end: usize,
+    pub edge_type: EdgeType,
+    pub word_id: WordId,
+    pub left_id: u32,
+    pub right_id: u32,
+    pub word_cost: i32,
+    /// The best left neighbour of the edge, if the edge is reachable from BOS.
+    pub left_edge: Option<u32>,
+    /// The connection cost between the best left neighbour and the edge.
+    pub connection_cost: Option<i32>,
+    /// The penalty added by the tokenize mode for the best left neighbour.
+    pub penalty_cost: Option<i32>,
+    /// The cost of the best path from BOS up to and including the edge.
+    pub path_cost: Option<i32>,
+    /// Whether the edge is on the best path.
+    pub best_path: bool,
+}
+
+/// A snapshot of a whole lattice, see `Lattice::dump`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LatticeDump {
+    pub text: String,
+    pub edges: Vec<EdgeDump>,
+}
+
+impl LatticeDump {
+    /// Renders the lattice as a Graphviz DOT graph.
+    /// Each edge points to its best left neighbour, and the best path is drawn in red.
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut dot = String::new();
+        dot.push_str("digraph lattice {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box];\n");
+        for edge in &self.edges {
+            let mut label = escape(&edge.surface);
+            if edge.id != BOS_NODE.0 && edge.id != EOS_NODE.0 {
+                let _ = write!(
+                    label,
+                    "\\n{:?} {}\\nword cost: {}",
+                    edge.edge_type, edge.word_id.0, edge.word_cost
+                );
+            }
+            if let Some(path_cost) = edge.path_cost {
+                let _ = write!(label, "\\npath cost: {}", path_cost);
+            }
+            let style = if edge.best_path {
+                ", color=red, penwidth=2"
+            } else {
+                ""
+            };
+            let _ = writeln!(dot, "  e{} [label=\"{}\"{}];", edge.id, label, style);
+        }
+        for edge in &self.edges {
+            if let Some(left_edge) = edge.left_edge {
+                let mut label = edge.connection_cost.unwrap_or_default().to_string();
+                if let Some(penalty_cost) = edge.penalty_cost.filter(|&cost| cost != 0) {
+                    let _ = write!(label, " + {}", penalty_cost);
+                }
+                let style = if edge.best_path {
+                    ", color=red, penwidth=2"
+                } else {
+                    ""
+                };
+                let _ = writeln!(
+                    dot,
+                    "  e{} -> e{} [label=\"{}\"{}];",
+                    left_edge, edge.id, label, style
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// A span that must be covered by exactly one token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstrainedSpan {
+    pub start: usize,
+    pub end: usize,
+    /// The word IDs allowed for the token. If `None`, any entry matching the span is allowed.
+    pub word_ids: Option<Vec<WordId>>,
 }
 
-impl Edge {
-    // TODO fix em
-    pub fn num_chars(&self) -> usize {
-        (self.stop_index - self.start_index) as usize / 3
+/// Constraints on the segmentation of a text for partial parsing.
+/// All offsets are byte offsets into the text and must lie on character boundaries.
+/// Spans must not overlap each other and boundaries must not fall inside a span.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Constraints {
+    /// Offsets at which a token boundary is forced.
+    pub boundaries: Vec<usize>,
+    /// Spans that are each forced to be a single token.
+    pub spans: Vec<ConstrainedSpan>,
+}
+
+impl Constraints {
+    pub fn is_empty(&self) -> bool {
+        self.boundaries.is_empty() && self.spans.is_empty()
+    }
+}
+
+/// Runtime overrides of the word costs of dictionary entries, applied as the edges are
+/// added to the lattice.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WordCostOverrides {
+    // The word cost of each overridden entry, or `None` if the entry is banned.
+    word_costs: HashMap<WordId, Option<i16>>,
+}
+
+impl WordCostOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the word cost of the entry.
+    pub fn set_cost(&mut self, word_id: WordId, word_cost: i16) {
+        self.word_costs.insert(word_id, Some(word_cost));
+    }
+
+    /// Keeps the entry out of the lattice.
+    pub fn ban(&mut self, word_id: WordId) {
+        self.word_costs.insert(word_id, None);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.word_costs.is_empty()
+    }
+
+    /// Returns the entry with its word cost overridden, or `None` if the entry is banned.
+    pub fn apply(&self, mut word_entry: WordEntry) -> Option<WordEntry> {
+        match self.word_costs.get(&word_entry.word_id) {
+            Some(Some(word_cost)) => {
+                word_entry.word_cost = *word_cost;
+                Some(word_entry)
+            }
+            Some(None) => None,
+            None => Some(word_entry),
+        }
     }
 }
 
@@ -133,13 +368,12 @@ pub struct Lattice {
     ends_at: Vec<Vec<EdgeId>>,
 }
 
-fn is_kanji(c: char) -> bool {
-    let c = c as u32;
-    (19968..=40879).contains(&c)
-}
-
-fn is_kanji_only(s: &str) -> bool {
-    s.chars().all(is_kanji)
+fn log_sum_exp(scores: impl Iterator<Item = f64> + Clone) -> f64 {
+    let max = scores.clone().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + scores.map(|score| (score - max).exp()).sum::<f64>().ln()
 }
 
 impl Lattice {
@@ -163,7 +397,6 @@ impl Lattice {
         }
     }
 
-    #[inline(never)]
     pub fn set_text(
         &mut self,
         dict: &PrefixDict,
@@ -172,19 +405,69 @@ impl Lattice {
         unknown_dictionary: &UnknownDictionary,
         text: &str,
         search_mode: &Mode,
+    ) {
+        self.set_text_with_constraints(
+            dict,
+            user_dict,
+            char_definitions,
+            unknown_dictionary,
+            text,
+            search_mode,
+            &Constraints::default(),
+            &WordCostOverrides::default(),
+        );
+    }
+
+    /// Builds the lattice like `set_text`, but drops every edge that crosses a forced
+    /// boundary or a forced span. A forced span only keeps the edges covering it exactly,
+    /// or a single unknown word inserted for it if no allowed entry covers it.
+    #[inline(never)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_text_with_constraints(
+        &mut self,
+        dict: &PrefixDict,
+        user_dict: &Option<&PrefixDict>,
+        char_definitions: &CharacterDefinitions,
+        unknown_dictionary: &UnknownDictionary,
+        text: &str,
+        search_mode: &Mode,
+        constraints: &Constraints,
+        word_cost_overrides: &WordCostOverrides,
     ) {
         let len = text.len();
         self.set_capacity(len);
 
         let start_edge_id = self.add_edge(Edge::default());
-        let end_edge_id = self.add_edge(Edge::default());
+        let end_edge_id = self.add_edge(Edge {
+            start_index: len as u32,
+            stop_index: len as u32,
+            ..Edge::default()
+        });
 
+        assert_eq!(BOS_NODE, start_edge_id);
         assert_eq!(EOS_NODE, end_edge_id);
         self.ends_at[0].push(start_edge_id);
         self.starts_at[len].push(end_edge_id);
 
-        // index of the last character of unknown word
-        let mut unknown_word_end: Option<usize> = None;
+        // Both ends of a span are boundaries as well.
+        let mut boundaries: Vec<usize> = constraints
+            .boundaries
+            .iter()
+            .copied()
+            .chain(
+                constraints
+                    .spans
+                    .iter()
+                    .flat_map(|span| [span.start, span.end]),
+            )
+            .filter(|&boundary| boundary > 0 && boundary < len)
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        let mut spans: Vec<&ConstrainedSpan> = constraints.spans.iter().collect();
+        spans.sort_unstable_by_key(|span| span.start);
+        let mut next_boundary = 0;
+        let mut next_span = 0;
 
         for start in 0..len {
             // No arc is ending here.
@@ -193,7 +476,32 @@ impl Lattice {
                 continue;
             }
 
-            let suffix = &text[start..];
+            while next_boundary < boundaries.len() && boundaries[next_boundary] <= start {
+                next_boundary += 1;
+            }
+            while next_span < spans.len() && spans[next_span].end <= start {
+                next_span += 1;
+            }
+            if let Some(span) = spans.get(next_span).filter(|span| span.start <= start) {
+                if span.start == start {
+                    self.add_span_edges(
+                        dict,
+                        user_dict,
+                        char_definitions,
+                        unknown_dictionary,
+                        text,
+                        search_mode,
+                        span,
+                        word_cost_overrides,
+                    );
+                }
+                // No other token may start inside a forced span.
+                continue;
+            }
+
+            // Words must not cross the next forced boundary.
+            let limit = boundaries.get(next_boundary).copied().unwrap_or(len);
+            let suffix = &text[start..limit];
 
             let mut found: bool = false;
 
@@ -201,14 +509,19 @@ impl Lattice {
             if user_dict.is_some() {
                 let dict = user_dict.as_ref().unwrap();
                 for (prefix_len, word_entry) in dict.prefix(suffix) {
+                    let word_entry = match word_cost_overrides.apply(word_entry) {
+                        Some(word_entry) => word_entry,
+                        None => continue,
+                    };
                     let edge = Edge {
-                        edge_type: EdgeType::KNOWN,
+                        edge_type: EdgeType::USER,
                         word_entry,
                         left_edge: None,
                         start_index: start as u32,
                         stop_index: (start + prefix_len) as u32,
                         path_cost: i32::max_value(),
-                        kanji_only: is_kanji_only(&suffix[..prefix_len]),
+                        penalty_cost: search_mode
+                            .word_penalty_cost(&suffix[..prefix_len], char_definitions),
                     };
                     self.add_edge_in_lattice(edge);
                     found = true;
@@ -218,6 +531,10 @@ impl Lattice {
             // we check all word starting at start, using the double array, like we would use
             // a prefix trie, and populate the lattice with as many edges
             for (prefix_len, word_entry) in dict.prefix(suffix) {
+                let word_entry = match word_cost_overrides.apply(word_entry) {
+                    Some(word_entry) => word_entry,
+                    None => continue,
+                };
                 let edge = Edge {
                     edge_type: EdgeType::KNOWN,
                     word_entry,
@@ -225,68 +542,168 @@ impl Lattice {
                     start_index: start as u32,
                     stop_index: (start + prefix_len) as u32,
                     path_cost: i32::max_value(),
-                    kanji_only: is_kanji_only(&suffix[..prefix_len]),
+                    penalty_cost: search_mode
+                        .word_penalty_cost(&suffix[..prefix_len], char_definitions),
                 };
                 self.add_edge_in_lattice(edge);
                 found = true;
             }
 
-            // In the case of normal mode, it doesn't process unknown word greedily.
-            if search_mode.is_search()
-                || unknown_word_end.map(|index| index <= start).unwrap_or(true)
-            {
-                if let Some(first_char) = suffix.chars().next() {
-                    let categories = char_definitions.lookup_categories(first_char);
-                    for (category_ord, &category) in categories.iter().enumerate() {
-                        unknown_word_end = self.process_unknown_word(
-                            char_definitions,
-                            unknown_dictionary,
-                            category,
-                            category_ord,
-                            unknown_word_end,
-                            start,
-                            suffix,
-                            found,
-                        );
-                    }
+            self.process_unknown_word(
+                char_definitions,
+                unknown_dictionary,
+                search_mode,
+                start,
+                suffix,
+                found,
+            );
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_span_edges(
+        &mut self,
+        dict: &PrefixDict,
+        user_dict: &Option<&PrefixDict>,
+        char_definitions: &CharacterDefinitions,
+        unknown_dictionary: &UnknownDictionary,
+        text: &str,
+        search_mode: &Mode,
+        span: &ConstrainedSpan,
+        word_cost_overrides: &WordCostOverrides,
+    ) {
+        let surface = &text[span.start..span.end];
+        let penalty_cost = search_mode.word_penalty_cost(surface, char_definitions);
+        let is_allowed = |word_entry: &WordEntry| match &span.word_ids {
+            Some(word_ids) => word_ids.contains(&word_entry.word_id),
+            None => true,
+        };
+
+        let mut found = false;
+        for dict in user_dict.iter().copied().chain(std::iter::once(dict)) {
+            for (prefix_len, word_entry) in dict.prefix(surface) {
+                if prefix_len != surface.len() || !is_allowed(&word_entry) {
+                    continue;
+                }
+                let word_entry = match word_cost_overrides.apply(word_entry) {
+                    Some(word_entry) => word_entry,
+                    None => continue,
+                };
+                let edge = Edge {
+                    edge_type: if dict.is_system {
+                        EdgeType::KNOWN
+                    } else {
+                        EdgeType::USER
+                    },
+                    word_entry,
+                    left_edge: None,
+                    start_index: span.start as u32,
+                    stop_index: span.end as u32,
+                    path_cost: i32::MAX,
+                    penalty_cost,
+                };
+                self.add_edge_in_lattice(edge);
+                found = true;
+            }
+        }
+        if found {
+            return;
+        }
+
+        // No allowed entry covers the span, so it is inserted as a single unknown word.
+        if let Some(first_char) = surface.chars().next() {
+            for &category in char_definitions.lookup_categories(first_char).iter() {
+                for &word_id in unknown_dictionary.lookup_word_ids(category) {
+                    let edge = Edge {
+                        edge_type: EdgeType::INSERTED,
+                        word_entry: unknown_dictionary.word_entry(word_id),
+                        left_edge: None,
+                        start_index: span.start as u32,
+                        stop_index: span.end as u32,
+                        path_cost: i32::MAX,
+                        penalty_cost,
+                    };
+                    self.add_edge_in_lattice(edge);
                 }
             }
         }
     }
 
-    #[allow(clippy::too_many_arguments)]
+    /// Adds unknown word candidates starting at `start`, following the semantics of MeCab.
+    ///
+    /// The candidates belong to the first category of the first character, and only span
+    /// grapheme clusters sharing a category with it. They are added if no dictionary entry starts
+    /// at `start` or the category is INVOKE. A GROUP category adds the whole run of such
+    /// characters if it is not longer than `MAX_GROUPING_SIZE`, and LENGTH adds the runs
+    /// of 1 to LENGTH grapheme clusters. If neither adds a candidate and no dictionary entry
+    /// was found, a single grapheme cluster is added so that the lattice stays connected.
     fn process_unknown_word(
         &mut self,
         char_definitions: &CharacterDefinitions,
         unknown_dictionary: &UnknownDictionary,
-        category: CategoryId,
-        category_ord: usize,
-        unknown_word_index: Option<usize>,
+        search_mode: &Mode,
         start: usize,
         suffix: &str,
         found: bool,
-    ) -> Option<usize> {
-        let mut unknown_word_num_chars: usize = 0;
+    ) {
+        let first_char = match suffix.chars().next() {
+            Some(first_char) => first_char,
+            None => return,
+        };
+        let categories = char_definitions.lookup_categories(first_char);
+        let category = match categories.first() {
+            Some(&category) => category,
+            None => return,
+        };
         let category_data = char_definitions.lookup_definition(category);
-        if category_data.invoke || !found {
-            unknown_word_num_chars = 1;
-            if category_data.group {
-                for c in suffix.chars().skip(1) {
-                    let categories = char_definitions.lookup_categories(c);
-                    if categories.len() > category_ord && categories[category_ord] == category {
-                        unknown_word_num_chars += 1;
-                    } else {
-                        break;
-                    }
+        if found && !category_data.invoke {
+            return;
+        }
+
+        // The byte length of each prefix of the run of characters of the same kind.
+        // The run is made of whole grapheme clusters, whose kind is the one of their first
+        // character, so that sequences such as ZWJ emoji are never split.
+        // Like MeCab, only the prefixes that can be used are collected, so that long runs
+        // are not scanned again at every start position.
+        let max_run_len = (MAX_GROUPING_SIZE + 1).max(category_data.length as usize);
+        let mut run_ends = Vec::new();
+        for (offset, grapheme) in suffix.grapheme_indices(true) {
+            if run_ends.len() == max_run_len {
+                break;
+            }
+            if offset > 0 {
+                let same_kind = match grapheme.chars().next() {
+                    Some(c) => char_definitions
+                        .lookup_categories(c)
+                        .iter()
+                        .any(|c_category| categories.contains(c_category)),
+                    None => false,
+                };
+                if !same_kind {
+                    break;
                 }
             }
+            run_ends.push(offset + grapheme.len());
+        }
+
+        let mut unknown_word_lens = Vec::new();
+        let mut group_len = None;
+        if category_data.group && run_ends.len() <= MAX_GROUPING_SIZE {
+            group_len = run_ends.last().copied();
+            unknown_word_lens.extend(group_len);
+        }
+        for &run_end in run_ends.iter().take(category_data.length as usize) {
+            if Some(run_end) != group_len {
+                unknown_word_lens.push(run_end);
+            }
+        }
+        if unknown_word_lens.is_empty() && !found {
+            unknown_word_lens.push(run_ends[0]);
         }
-        if unknown_word_num_chars > 0 {
-            // optimize
-            let unknown_word = suffix
-                .chars()
-                .take(unknown_word_num_chars)
-                .collect::<String>();
+
+        for unknown_word_len in unknown_word_lens {
+            let penalty_cost =
+                search_mode.word_penalty_cost(&suffix[..unknown_word_len], char_definitions);
             for &word_id in unknown_dictionary.lookup_word_ids(category) {
                 let word_entry = unknown_dictionary.word_entry(word_id);
                 let edge = Edge {
@@ -294,15 +711,13 @@ impl Lattice {
                     word_entry,
                     left_edge: None,
                     start_index: start as u32,
-                    stop_index: (start + unknown_word.len()) as u32,
+                    stop_index: (start + unknown_word_len) as u32,
                     path_cost: i32::max_value(),
-                    kanji_only: is_kanji_only(&unknown_word[..]),
+                    penalty_cost,
                 };
                 self.add_edge_in_lattice(edge);
             }
-            return Some(start + unknown_word.len());
         }
-        unknown_word_index
     }
 
     fn add_edge_in_lattice(&mut self, edge: Edge) {
@@ -323,6 +738,9 @@ impl Lattice {
         &self.edges[edge_id.0 as usize]
     }
 
+    /// Finds the best path from BOS to each edge.
+    ///
+    /// `mode` must be the mode the text was set with.
     #[inline(never)]
     pub fn calculate_path_costs(&mut self, cost_matrix: &ConnectionCostMatrix, mode: &Mode) {
         let text_len = self.starts_at.len();
@@ -354,8 +772,15 @@ impl Lattice {
 
     pub fn tokens_offset(&self) -> Vec<(usize, WordId)> {
         let mut offsets = Vec::new();
+        self.tokens_offset_into(&mut offsets);
+        offsets
+    }
+
+    /// Same as `tokens_offset`, but writes into `offsets` so that its buffer can be reused.
+    /// `offsets` is cleared first.
+    pub fn tokens_offset_into(&self, offsets: &mut Vec<(usize, WordId)>) {
+        offsets.clear();
         let mut edge_id = EOS_NODE;
-        let _edge = self.edge(EOS_NODE);
         loop {
             let edge = self.edge(edge_id);
             if let Some(left_edge_id) = edge.left_edge {
@@ -367,6 +792,792 @@ impl Lattice {
         }
         offsets.reverse();
         offsets.pop();
-        offsets
+    }
+
+    /// Writes the edges of the best path into `path`, from left to right, without BOS and EOS.
+    /// `path` is cleared first.
+    pub fn best_path_into(&self, path: &mut Vec<EdgeId>) {
+        path.clear();
+        let mut edge_id = EOS_NODE;
+        while let Some(left_edge_id) = self.edge(edge_id).left_edge {
+            if left_edge_id != BOS_NODE {
+                path.push(left_edge_id);
+            }
+            edge_id = left_edge_id;
+        }
+        path.reverse();
+    }
+
+    /// Returns up to `n` paths through the lattice in ascending order of their total cost.
+    ///
+    /// `calculate_path_costs` must be called beforehand with the same mode. Its path costs are
+    /// the exact cost of the best path from BOS to each edge, so they are used as the heuristic
+    /// of a backward A* search starting from EOS.
+    pub fn nbest(
+        &self,
+        cost_matrix: &ConnectionCostMatrix,
+        mode: &Mode,
+        n: usize,
+    ) -> Vec<(Vec<(usize, WordId)>, i32)> {
+        self.nbest_paths(cost_matrix, mode, n)
+            .into_iter()
+            .map(|(path, cost)| {
+                let offsets = path
+                    .iter()
+                    .map(|&edge_id| {
+                        let edge = self.edge(edge_id);
+                        (edge.start_index as usize, edge.word_entry.word_id)
+                    })
+                    .collect();
+                (offsets, cost)
+            })
+            .collect()
+    }
+
+    /// Same as `nbest`, but returns the edges of each path, without BOS and EOS.
+    pub fn nbest_paths(
+        &self,
+        cost_matrix: &ConnectionCostMatrix,
+        mode: &Mode,
+        n: usize,
+    ) -> Vec<(Vec<EdgeId>, i32)> {
+        let mut paths = Vec::new();
+        if n == 0 || self.edge(EOS_NODE).left_edge.is_none() {
+            return paths;
+        }
+
+        // Each node holds an edge, the cost from the end of that edge to EOS,
+        // and the index of the node on its right.
+        let mut nodes: Vec<(EdgeId, i32, Option<usize>)> = vec![(EOS_NODE, 0, None)];
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((self.edge(EOS_NODE).path_cost, 0usize)));
+
+        while let Some(Reverse((total_cost, node_index))) = queue.pop() {
+            let (edge_id, backward_cost, _) = nodes[node_index];
+            if edge_id == BOS_NODE {
+                paths.push((self.nbest_path(&nodes, node_index), total_cost));
+                if paths.len() >= n {
+                    break;
+                }
+                continue;
+            }
+
+            let edge = self.edge(edge_id);
+            let backward_cost = backward_cost + edge.word_entry.word_cost as i32;
+            for &left_edge_id in &self.ends_at[edge.start_index as usize] {
+                let left_edge = self.edge(left_edge_id);
+                if left_edge_id != BOS_NODE && left_edge.left_edge.is_none() {
+                    // Not reachable from BOS.
+                    continue;
+                }
+                let cost = backward_cost
+                    + cost_matrix.cost(left_edge.word_entry.right_id(), edge.word_entry.left_id())
+                    + mode.penalty_cost(left_edge);
+                nodes.push((left_edge_id, cost, Some(node_index)));
+                queue.push(Reverse((left_edge.path_cost + cost, nodes.len() - 1)));
+            }
+        }
+
+        paths
+    }
+
+    /// Returns the marginal probability of each token on the best path, in the same order
+    /// as `tokens_offset`.
+    ///
+    /// The probability of a path is proportional to `exp(-cost / temperature)`, so a higher
+    /// temperature spreads the probability over more paths. Forward and backward scores are
+    /// accumulated in the log domain to avoid underflow.
+    pub fn marginals(
+        &self,
+        cost_matrix: &ConnectionCostMatrix,
+        mode: &Mode,
+        temperature: f64,
+    ) -> Vec<f64> {
+        let num_edges = self.edges.len();
+        let text_len = self.starts_at.len();
+        let connection_score = |left_edge: &Edge, right_edge: &Edge| {
+            -((cost_matrix.cost(
+                left_edge.word_entry.right_id(),
+                right_edge.word_entry.left_id(),
+            ) + mode.penalty_cost(left_edge)) as f64)
+                / temperature
+        };
+        let word_score = |edge: &Edge| -(edge.word_entry.word_cost as f64) / temperature;
+
+        // Forward scores include the word cost of the edge itself.
+        let mut alpha = vec![f64::NEG_INFINITY; num_edges];
+        alpha[BOS_NODE.0 as usize] = 0.0;
+        for i in 0..text_len {
+            for &right_edge_id in &self.starts_at[i] {
+                let right_edge = self.edge(right_edge_id);
+                let score = log_sum_exp(self.ends_at[i].iter().map(|&left_edge_id| {
+                    alpha[left_edge_id.0 as usize]
+                        + connection_score(self.edge(left_edge_id), right_edge)
+                }));
+                alpha[right_edge_id.0 as usize] = score + word_score(right_edge);
+            }
+        }
+
+        // Backward scores exclude the word cost of the edge itself.
+        let mut beta = vec![f64::NEG_INFINITY; num_edges];
+        beta[EOS_NODE.0 as usize] = 0.0;
+        for i in (0..text_len).rev() {
+            for &left_edge_id in &self.ends_at[i] {
+                let left_edge = self.edge(left_edge_id);
+                beta[left_edge_id.0 as usize] =
+                    log_sum_exp(self.starts_at[i].iter().map(|&right_edge_id| {
+                        let right_edge = self.edge(right_edge_id);
+                        beta[right_edge_id.0 as usize]
+                            + connection_score(left_edge, right_edge)
+                            + word_score(right_edge)
+                    }));
+            }
+        }
+
+        let log_z = alpha[EOS_NODE.0 as usize];
+        let mut marginals = Vec::new();
+        let mut edge_id = EOS_NODE;
+        while let Some(left_edge_id) = self.edge(edge_id).left_edge {
+            if left_edge_id != BOS_NODE {
+                let i = left_edge_id.0 as usize;
+                marginals.push((alpha[i] + beta[i] - log_z).exp().min(1.0));
+            }
+            edge_id = left_edge_id;
+        }
+        marginals.reverse();
+        marginals
+    }
+
+    /// Returns every edge of the lattice with its costs.
+    ///
+    /// `calculate_path_costs` must be called beforehand with the same cost matrix and mode.
+    pub fn dump(&self, text: &str, cost_matrix: &ConnectionCostMatrix, mode: &Mode) -> LatticeDump {
+        let mut best_path = vec![false; self.edges.len()];
+        if self.edge(EOS_NODE).left_edge.is_some() {
+            let mut edge_id = Some(EOS_NODE);
+            while let Some(id) = edge_id {
+                best_path[id.0 as usize] = true;
+                edge_id = self.edge(id).left_edge;
+            }
+        }
+
+        let edges = self
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(id, edge)| {
+                let edge_id = EdgeId(id as u32);
+                let surface = match edge_id {
+                    BOS_NODE => "BOS",
+                    EOS_NODE => "EOS",
+                    _ => &text[edge.start_index as usize..edge.stop_index as usize],
+                };
+                let left_edge = edge.left_edge.map(|left_edge_id| self.edge(left_edge_id));
+                EdgeDump {
+                    id: id as u32,
+                    surface: surface.to_string(),
+                    start: edge.start_index as usize,
+                    end: edge.stop_index as usize,
+                    edge_type: edge.edge_type,
+                    word_id: edge.word_entry.word_id,
+                    left_id: edge.word_entry.left_id(),
+                    right_id: edge.word_entry.right_id(),
+                    word_cost: edge.word_entry.word_cost as i32,
+                    left_edge: edge.left_edge.map(|left_edge_id| left_edge_id.0),
+                    connection_cost: left_edge.map(|left_edge| {
+                        cost_matrix.cost(left_edge.word_entry.right_id(), edge.word_entry.left_id())
+                    }),
+                    penalty_cost: left_edge.map(|left_edge| mode.penalty_cost(left_edge)),
+                    path_cost: if edge_id == BOS_NODE || left_edge.is_some() {
+                        Some(edge.path_cost)
+                    } else {
+                        None
+                    },
+                    best_path: best_path[id],
+                }
+            })
+            .collect();
+
+        LatticeDump {
+            text: text.to_string(),
+            edges,
+        }
+    }
+
+    fn nbest_path(&self, nodes: &[(EdgeId, i32, Option<usize>)], bos_index: usize) -> Vec<EdgeId> {
+        let mut path = Vec::new();
+        let mut next = nodes[bos_index].2;
+        while let Some(node_index) = next {
+            let (edge_id, _, right) = nodes[node_index];
+            if edge_id == EOS_NODE {
+                break;
+            }
+            path.push(edge_id);
+            next = right;
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use byteorder::{LittleEndian, WriteBytesExt};
+    use yada::{builder::DoubleArrayBuilder, DoubleArray};
+
+    use crate::{
+        character_definition::{CharacterDefinitions, CharacterDefinitionsBuilder},
+        connection::ConnectionCostMatrix,
+        prefix_dict::PrefixDict,
+        unknown_dictionary::{parse_unk, UnknownDictionary},
+        viterbi::{
+            CategoryPenalty, ConstrainedSpan, Constraints, EdgeType, Lattice, Mode, Penalty,
+            WordCostOverrides, MAX_GROUPING_SIZE,
+        },
+        word_entry::{WordEntry, WordId},
+    };
+
+    fn build_prefix_dict(entries: &[(&str, i16, u16, u16)]) -> PrefixDict {
+        let mut word_entry_map: BTreeMap<&str, Vec<WordEntry>> = BTreeMap::new();
+        for (word_id, (surface, word_cost, left_id, right_id)) in entries.iter().enumerate() {
+            word_entry_map.entry(surface).or_default().push(WordEntry {
+                word_id: WordId(word_id as u32, true),
+                word_cost: *word_cost,
+                left_id: *left_id,
+                right_id: *right_id,
+            });
+        }
+
+        let mut id = 0u32;
+        let mut keyset: Vec<(&[u8], u32)> = Vec::new();
+        let mut vals_data = Vec::new();
+        for (surface, word_entries) in &word_entry_map {
+            let len = word_entries.len() as u32;
+            keyset.push((surface.as_bytes(), (id << 5) | len));
+            id += len;
+            for word_entry in word_entries {
+                word_entry.serialize(&mut vals_data).unwrap();
+            }
+        }
+
+        PrefixDict {
+            da: DoubleArray::new(DoubleArrayBuilder::build(&keyset).unwrap().into()),
+            vals_data: vals_data.into(),
+            is_system: true,
+        }
+    }
+
+    fn build_cost_matrix(size: u16, costs: &[(u16, u16, i16)]) -> ConnectionCostMatrix {
+        let mut matrix = vec![0i16; (size * size) as usize];
+        for &(forward_id, backward_id, cost) in costs {
+            matrix[(backward_id + forward_id * size) as usize] = cost;
+        }
+
+        let mut data = Vec::new();
+        data.write_i16::<LittleEndian>(size as i16).unwrap();
+        data.write_i16::<LittleEndian>(size as i16).unwrap();
+        for cost in matrix {
+            data.write_i16::<LittleEndian>(cost).unwrap();
+        }
+        ConnectionCostMatrix::load(&data)
+    }
+
+    fn build_char_definitions() -> CharacterDefinitions {
+        let mut builder = CharacterDefinitionsBuilder::default();
+        builder.parse("DEFAULT 0 1 0").unwrap();
+        builder.build()
+    }
+
+    fn build_unknown_dictionary(char_definitions: &CharacterDefinitions) -> UnknownDictionary {
+        parse_unk(char_definitions.categories(), "DEFAULT,0,0,10000", 4, 0).unwrap()
+    }
+
+    /// The dictionary of "abc", whose paths are "ab/c" (250), "a/bc" (280) and "a/b/c" (300).
+    struct AbcFixture {
+        dict: PrefixDict,
+        cost_matrix: ConnectionCostMatrix,
+        char_definitions: CharacterDefinitions,
+        unknown_dictionary: UnknownDictionary,
+    }
+
+    impl AbcFixture {
+        fn new() -> Self {
+            let dict = build_prefix_dict(&[
+                ("a", 100, 0, 0),
+                ("ab", 150, 0, 0),
+                ("b", 100, 0, 0),
+                ("bc", 180, 0, 0),
+                ("c", 100, 0, 0),
+            ]);
+            let char_definitions = build_char_definitions();
+            let unknown_dictionary = build_unknown_dictionary(&char_definitions);
+            AbcFixture {
+                dict,
+                cost_matrix: build_cost_matrix(1, &[]),
+                char_definitions,
+                unknown_dictionary,
+            }
+        }
+
+        /// Returns the lattice of "abc" in the normal mode, with its path costs calculated.
+        fn lattice(
+            &self,
+            constraints: &Constraints,
+            word_cost_overrides: &WordCostOverrides,
+        ) -> Lattice {
+            let mut lattice = Lattice::default();
+            lattice.set_text_with_constraints(
+                &self.dict,
+                &None,
+                &self.char_definitions,
+                &self.unknown_dictionary,
+                "abc",
+                &Mode::Normal,
+                constraints,
+                word_cost_overrides,
+            );
+            lattice.calculate_path_costs(&self.cost_matrix, &Mode::Normal);
+            lattice
+        }
+    }
+
+    #[test]
+    fn test_nbest() {
+        let fixture = AbcFixture::new();
+        let cost_matrix = &fixture.cost_matrix;
+        let lattice = fixture.lattice(&Constraints::default(), &WordCostOverrides::default());
+
+        let paths = lattice.nbest(cost_matrix, &Mode::Normal, 10);
+        let segmentations: Vec<(Vec<usize>, i32)> = paths
+            .iter()
+            .map(|(offsets, cost)| (offsets.iter().map(|(start, _)| *start).collect(), *cost))
+            .collect();
+        assert_eq!(
+            segmentations,
+            vec![(vec![0, 2], 250), (vec![0, 1], 280), (vec![0, 1, 2], 300)]
+        );
+
+        // The best path is identical to the one found by the Viterbi algorithm.
+        assert_eq!(paths[0].0, lattice.tokens_offset());
+
+        assert_eq!(lattice.nbest(cost_matrix, &Mode::Normal, 2).len(), 2);
+        assert!(lattice.nbest(cost_matrix, &Mode::Normal, 0).is_empty());
+    }
+
+    #[test]
+    fn test_best_path_user_dictionary() {
+        let dict = build_prefix_dict(&[("a", 100, 0, 0), ("b", 100, 0, 0)]);
+        let mut user_dict = build_prefix_dict(&[("ab", 50, 0, 0)]);
+        user_dict.is_system = false;
+        let cost_matrix = build_cost_matrix(1, &[]);
+        let char_definitions = build_char_definitions();
+        let unknown_dictionary = build_unknown_dictionary(&char_definitions);
+
+        let mut lattice = Lattice::default();
+        lattice.set_text(
+            &dict,
+            &Some(&user_dict),
+            &char_definitions,
+            &unknown_dictionary,
+            "abc",
+            &Mode::Normal,
+        );
+        lattice.calculate_path_costs(&cost_matrix, &Mode::Normal);
+
+        let mut path = Vec::new();
+        lattice.best_path_into(&mut path);
+        let edges: Vec<(EdgeType, u32, i32)> = path
+            .iter()
+            .map(|&edge_id| {
+                let edge = lattice.edge(edge_id);
+                (edge.edge_type, edge.stop_index, edge.path_cost)
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![(EdgeType::USER, 2, 50), (EdgeType::UNKNOWN, 3, 10050)]
+        );
+
+        let paths = lattice.nbest_paths(&cost_matrix, &Mode::Normal, 1);
+        assert_eq!(paths[0].0, path);
+    }
+
+    #[test]
+    fn test_mode_from_str() {
+        assert_eq!("normal".parse::<Mode>().unwrap(), Mode::Normal);
+        assert_eq!(
+            "search".parse::<Mode>().unwrap(),
+            Mode::Search(Penalty::default())
+        );
+        assert!("search".parse::<Mode>().unwrap().is_search());
+        assert_eq!(
+            "extended".parse::<Mode>().unwrap(),
+            Mode::Extended(Penalty::default())
+        );
+        assert!("unknown".parse::<Mode>().is_err());
+    }
+
+    #[test]
+    fn test_left_and_right_context_ids() {
+        // The right context id of "a" and the left context id of "b" differ from their
+        // other context ids, and only that pair has an expensive connection.
+        let dict = build_prefix_dict(&[("a", 0, 1, 2), ("ab", 100, 1, 1), ("b", 0, 1, 1)]);
+        let cost_matrix = build_cost_matrix(3, &[(2, 1, 1000)]);
+        let char_definitions = build_char_definitions();
+        let unknown_dictionary = build_unknown_dictionary(&char_definitions);
+
+        let mut lattice = Lattice::default();
+        lattice.set_text(
+            &dict,
+            &None,
+            &char_definitions,
+            &unknown_dictionary,
+            "ab",
+            &Mode::Normal,
+        );
+        lattice.calculate_path_costs(&cost_matrix, &Mode::Normal);
+
+        let paths = lattice.nbest(&cost_matrix, &Mode::Normal, 2);
+        assert_eq!(paths[0].1, 100);
+        assert_eq!(paths[1].1, 1000);
+        assert_eq!(lattice.tokens_offset().len(), 1);
+    }
+
+    #[test]
+    fn test_set_text_with_constraints() {
+        let fixture = AbcFixture::new();
+        let tokenize = |constraints: &Constraints| {
+            fixture
+                .lattice(constraints, &WordCostOverrides::default())
+                .tokens_offset()
+        };
+
+        // "ab" / "c" is the best path without constraints.
+        assert_eq!(
+            tokenize(&Constraints::default()),
+            vec![(0, WordId(1, true)), (2, WordId(4, true))]
+        );
+
+        // A boundary between "a" and "b" prunes "ab".
+        assert_eq!(
+            tokenize(&Constraints {
+                boundaries: vec![1],
+                spans: vec![],
+            }),
+            vec![(0, WordId(0, true)), (1, WordId(3, true))]
+        );
+
+        // A span only keeps the allowed entries covering it exactly.
+        assert_eq!(
+            tokenize(&Constraints {
+                boundaries: vec![],
+                spans: vec![ConstrainedSpan {
+                    start: 1,
+                    end: 2,
+                    word_ids: Some(vec![WordId(2, true)]),
+                }],
+            }),
+            vec![
+                (0, WordId(0, true)),
+                (1, WordId(2, true)),
+                (2, WordId(4, true))
+            ]
+        );
+
+        // A span without any matching entry becomes a single unknown word.
+        let offsets = tokenize(&Constraints {
+            boundaries: vec![],
+            spans: vec![ConstrainedSpan {
+                start: 0,
+                end: 3,
+                word_ids: None,
+            }],
+        });
+        assert_eq!(offsets.len(), 1);
+        assert!(offsets[0].1.is_unknown());
+    }
+
+    #[test]
+    fn test_word_cost_overrides() {
+        let fixture = AbcFixture::new();
+        let tokenize = |constraints: &Constraints, word_cost_overrides: &WordCostOverrides| {
+            fixture
+                .lattice(constraints, word_cost_overrides)
+                .tokens_offset()
+        };
+
+        // A cheaper "bc" beats "ab" / "c".
+        let mut word_cost_overrides = WordCostOverrides::new();
+        word_cost_overrides.set_cost(WordId(3, true), 10);
+        assert_eq!(
+            tokenize(&Constraints::default(), &word_cost_overrides),
+            vec![(0, WordId(0, true)), (1, WordId(3, true))]
+        );
+
+        // A banned entry is never added, even in a span allowing it.
+        let mut word_cost_overrides = WordCostOverrides::new();
+        word_cost_overrides.ban(WordId(1, true));
+        assert_eq!(
+            tokenize(&Constraints::default(), &word_cost_overrides),
+            vec![(0, WordId(0, true)), (1, WordId(3, true))]
+        );
+        let offsets = tokenize(
+            &Constraints {
+                boundaries: vec![],
+                spans: vec![ConstrainedSpan {
+                    start: 0,
+                    end: 2,
+                    word_ids: None,
+                }],
+            },
+            &word_cost_overrides,
+        );
+        assert!(offsets[0].1.is_unknown());
+
+        // Without any other entry, a banned one leaves an unknown word.
+        word_cost_overrides.ban(WordId(0, true));
+        let offsets = tokenize(&Constraints::default(), &word_cost_overrides);
+        assert!(offsets[0].1.is_unknown());
+    }
+
+    #[test]
+    fn test_dump() {
+        let fixture = AbcFixture::new();
+        let cost_matrix = &fixture.cost_matrix;
+        let lattice = fixture.lattice(&Constraints::default(), &WordCostOverrides::default());
+
+        let dump = lattice.dump("abc", cost_matrix, &Mode::Normal);
+        assert_eq!(dump.edges[0].surface, "BOS");
+        assert_eq!(dump.edges[1].surface, "EOS");
+        assert_eq!(dump.edges[1].path_cost, Some(250));
+
+        let best_path = dump
+            .edges
+            .iter()
+            .filter(|edge| edge.best_path)
+            .map(|edge| edge.surface.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(best_path, vec!["BOS", "EOS", "ab", "c"]);
+
+        let edge = dump.edges.iter().find(|edge| edge.surface == "bc").unwrap();
+        assert_eq!(edge.edge_type, EdgeType::KNOWN);
+        assert_eq!(edge.word_cost, 180);
+        assert_eq!(edge.connection_cost, Some(0));
+        assert_eq!(edge.path_cost, Some(280));
+        assert!(!edge.best_path);
+
+        let dot = dump.to_dot();
+        assert!(dot.starts_with("digraph lattice {"));
+        assert!(dot.contains("label=\"ab\\nKNOWN 1\\nword cost: 150\\npath cost: 150\", color=red"));
+    }
+
+    #[test]
+    fn test_marginals() {
+        let fixture = AbcFixture::new();
+        let cost_matrix = &fixture.cost_matrix;
+        let lattice = fixture.lattice(&Constraints::default(), &WordCostOverrides::default());
+
+        let temperature = 50.0;
+        let weight = |cost: f64| (-cost / temperature).exp();
+        let z = weight(250.0) + weight(280.0) + weight(300.0);
+
+        let marginals = lattice.marginals(cost_matrix, &Mode::Normal, temperature);
+        assert_eq!(marginals.len(), 2);
+        // "ab" only appears in the best path, while "c" also appears in "a/b/c".
+        assert!((marginals[0] - weight(250.0) / z).abs() < 1e-9);
+        assert!((marginals[1] - (weight(250.0) + weight(300.0)) / z).abs() < 1e-9);
+
+        // A higher temperature lowers the confidence of the best path.
+        let marginals = lattice.marginals(cost_matrix, &Mode::Normal, 1000.0);
+        assert!(marginals[0] < weight(250.0) / z);
+    }
+
+    #[test]
+    fn test_unknown_word_candidates() {
+        let mut char_definitions_builder = CharacterDefinitionsBuilder::default();
+        char_definitions_builder
+            .parse(
+                "DEFAULT 0 1 0\n\
+                 ALPHA 1 1 0\n\
+                 KATAKANA 1 1 2\n\
+                 KANJI 0 0 2\n\
+                 0x0041..0x005A ALPHA\n\
+                 0x0061..0x007A ALPHA\n\
+                 0x30A1..0x30FF KATAKANA\n\
+                 0x4E00..0x9FFF KANJI\n",
+            )
+            .unwrap();
+        let char_definitions = char_definitions_builder.build();
+        let unknown_dictionary = parse_unk(
+            char_definitions.categories(),
+            "DEFAULT,0,0,1000\nALPHA,0,0,1000\nKATAKANA,0,0,1000\nKANJI,0,0,1000",
+            4,
+            0,
+        )
+        .unwrap();
+        let dict = build_prefix_dict(&[("漢", 100, 0, 0)]);
+
+        let mut lattice = Lattice::default();
+        let mut unknown_word_lens = |text: &str| {
+            lattice.set_text(
+                &dict,
+                &None,
+                &char_definitions,
+                &unknown_dictionary,
+                text,
+                &Mode::Normal,
+            );
+            let mut lens = lattice
+                .edges
+                .iter()
+                .filter(|edge| matches!(edge.edge_type, EdgeType::UNKNOWN))
+                .filter(|edge| edge.start_index == 0)
+                .map(|edge| text[..edge.stop_index as usize].chars().count())
+                .collect::<Vec<_>>();
+            lens.sort_unstable();
+            lens
+        };
+
+        // KATAKANA groups the whole run and adds runs of 1 and 2 characters.
+        assert_eq!(unknown_word_lens("トートバッグ"), vec![1, 2, 6]);
+        // The run stops at a character of another kind.
+        assert_eq!(unknown_word_lens("abc1"), vec![3]);
+        // KANJI does not group, and is not invoked if a dictionary entry is found.
+        assert_eq!(unknown_word_lens("字漢字"), vec![1, 2]);
+        assert_eq!(unknown_word_lens("漢字"), Vec::<usize>::new());
+        // DEFAULT groups since no dictionary entry is found.
+        assert_eq!(unknown_word_lens("@@"), vec![2]);
+        // Runs longer than the maximum grouping size fall back to a single character.
+        assert_eq!(unknown_word_lens(&"a".repeat(30)), vec![1]);
+    }
+
+    #[test]
+    fn test_long_unknown_word_run() {
+        let mut char_definitions_builder = CharacterDefinitionsBuilder::default();
+        char_definitions_builder
+            .parse("DEFAULT 0 1 0\nALPHA 1 1 0\n0x0061..0x007A ALPHA\n")
+            .unwrap();
+        let char_definitions = char_definitions_builder.build();
+        let unknown_dictionary = parse_unk(
+            char_definitions.categories(),
+            "DEFAULT,0,0,1000\nALPHA,0,0,1000",
+            4,
+            0,
+        )
+        .unwrap();
+        let dict = build_prefix_dict(&[("b", 100, 0, 0)]);
+        let cost_matrix = build_cost_matrix(1, &[]);
+
+        // The run is too long to be grouped, so it falls back to single characters.
+        // Scanning the whole run at every position would take quadratic time.
+        let text = "a".repeat(100_000);
+        let mut lattice = Lattice::default();
+        lattice.set_text(
+            &dict,
+            &None,
+            &char_definitions,
+            &unknown_dictionary,
+            &text,
+            &Mode::Normal,
+        );
+        lattice.calculate_path_costs(&cost_matrix, &Mode::Normal);
+        // Only the last 24 characters are short enough to be grouped.
+        let offsets = lattice.tokens_offset();
+        assert_eq!(offsets.len(), 100_000 - MAX_GROUPING_SIZE + 1);
+        assert_eq!(offsets.last().unwrap().0, 100_000 - MAX_GROUPING_SIZE);
+    }
+
+    #[test]
+    fn test_unknown_word_grapheme_clusters() {
+        let mut char_definitions_builder = CharacterDefinitionsBuilder::default();
+        char_definitions_builder
+            .parse(
+                "DEFAULT 0 1 0\n\
+                 EMOJI 1 0 1\n\
+                 0x1F300..0x1FAFF EMOJI\n",
+            )
+            .unwrap();
+        let char_definitions = char_definitions_builder.build();
+        let unknown_dictionary = parse_unk(
+            char_definitions.categories(),
+            "DEFAULT,0,0,1000\nEMOJI,0,0,1000",
+            4,
+            0,
+        )
+        .unwrap();
+        let dict = build_prefix_dict(&[("a", 100, 0, 0)]);
+        let cost_matrix = build_cost_matrix(1, &[]);
+
+        // A family emoji made of three emoji joined by ZWJ (U+200D), and a thumbs up.
+        let text = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{1F44D}";
+        let mut lattice = Lattice::default();
+        lattice.set_text(
+            &dict,
+            &None,
+            &char_definitions,
+            &unknown_dictionary,
+            text,
+            &Mode::Normal,
+        );
+        lattice.calculate_path_costs(&cost_matrix, &Mode::Normal);
+
+        let offsets = lattice.tokens_offset();
+        assert_eq!(
+            offsets.iter().map(|(start, _)| *start).collect::<Vec<_>>(),
+            vec![0, 18]
+        );
+    }
+
+    #[test]
+    fn test_penalty() {
+        let mut char_definitions_builder = CharacterDefinitionsBuilder::default();
+        char_definitions_builder
+            .parse(
+                "DEFAULT 0 1 0\n\
+                 ALPHA 1 1 0\n\
+                 KANJI 0 0 2\n\
+                 HANGUL 0 1 0\n\
+                 0x0061..0x007A ALPHA\n\
+                 0x3400..0x4DBF KANJI\n\
+                 0x4E00..0x9FFF KANJI\n\
+                 0xAC00..0xD7A3 HANGUL\n",
+            )
+            .unwrap();
+        let char_definitions = char_definitions_builder.build();
+
+        let penalty = Penalty::default();
+        // Characters are counted regardless of their UTF-8 length.
+        assert_eq!(penalty.penalty("関西国際", &char_definitions), 6000);
+        assert_eq!(penalty.penalty("関西", &char_definitions), 0);
+        // Extension A kanji belong to KANJI as well.
+        assert_eq!(penalty.penalty("㐀㐁㐂", &char_definitions), 3000);
+        assert_eq!(penalty.penalty("abcdefghij", &char_definitions), 5100);
+        assert_eq!(penalty.penalty("関西abc", &char_definitions), 0);
+        assert_eq!(
+            penalty.penalty("형태소분석기입니다", &char_definitions),
+            3400
+        );
+
+        let penalty = Penalty {
+            category_penalties: vec![CategoryPenalty {
+                category: "HANGUL".to_string(),
+                length_threshold: 3,
+                length_penalty: 1000,
+            }],
+            ..Penalty::default()
+        };
+        assert_eq!(
+            penalty.penalty("형태소분석기입니다", &char_definitions),
+            6000
+        );
+        assert_eq!(penalty.penalty("関西国際", &char_definitions), 6000);
+
+        let mode = Mode::Decompose(penalty);
+        assert_eq!(mode.word_penalty_cost("형태소", &char_definitions), 0);
+        assert_eq!(
+            Mode::Normal.word_penalty_cost("형태소분석기입니다", &char_definitions),
+            0
+        );
     }
 }
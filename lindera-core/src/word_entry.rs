@@ -3,12 +3,28 @@ use std::io;
 use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+// The IDs of the entries of the unknown dictionary have the highest bit set,
+// which the IDs of the dictionary words never reach.
+const UNKNOWN_WORD_ID_FLAG: u32 = 1 << 31;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct WordId(pub u32, pub bool);
 
 impl WordId {
+    /// Creates the ID of the entry of the unknown dictionary at `index`.
+    pub fn unknown(index: u32) -> Self {
+        WordId(UNKNOWN_WORD_ID_FLAG | index, true)
+    }
     pub fn is_unknown(&self) -> bool {
-        self.0 == std::u32::MAX
+        self.0 & UNKNOWN_WORD_ID_FLAG != 0
+    }
+    /// Returns the index of the entry in the unknown dictionary, if the word is unknown.
+    pub fn unknown_index(&self) -> Option<u32> {
+        if self.is_unknown() {
+            Some(self.0 & !UNKNOWN_WORD_ID_FLAG)
+        } else {
+            None
+        }
     }
     pub fn is_system(&self) -> bool {
         self.1
@@ -25,35 +41,39 @@ impl Default for WordId {
 pub struct WordEntry {
     pub word_id: WordId,
     pub word_cost: i16,
-    pub cost_id: u16,
+    pub left_id: u16,
+    pub right_id: u16,
 }
 
 impl WordEntry {
-    pub const SERIALIZED_LEN: usize = 8;
+    pub const SERIALIZED_LEN: usize = 10;
 
     pub fn left_id(&self) -> u32 {
-        self.cost_id as u32
+        self.left_id as u32
     }
 
     pub fn right_id(&self) -> u32 {
-        self.cost_id as u32
+        self.right_id as u32
     }
 
     pub fn serialize<W: io::Write>(&self, wtr: &mut W) -> io::Result<()> {
         wtr.write_u32::<LittleEndian>(self.word_id.0)?;
         wtr.write_i16::<LittleEndian>(self.word_cost)?;
-        wtr.write_u16::<LittleEndian>(self.cost_id)?;
+        wtr.write_u16::<LittleEndian>(self.left_id)?;
+        wtr.write_u16::<LittleEndian>(self.right_id)?;
         Ok(())
     }
 
     pub fn deserialize(data: &[u8], is_system_entry: bool) -> WordEntry {
         let word_id = WordId(LittleEndian::read_u32(&data[0..4]), is_system_entry);
         let word_cost = LittleEndian::read_i16(&data[4..6]);
-        let cost_id = LittleEndian::read_u16(&data[6..8]);
+        let left_id = LittleEndian::read_u16(&data[6..8]);
+        let right_id = LittleEndian::read_u16(&data[8..10]);
         WordEntry {
             word_id,
             word_cost,
-            cost_id,
+            left_id,
+            right_id,
         }
     }
 }
@@ -68,7 +88,8 @@ mod tests {
         let word_entry = WordEntry {
             word_id: WordId(1u32, true),
             word_cost: -17i16,
-            cost_id: 1411u16,
+            left_id: 1411u16,
+            right_id: 1412u16,
         };
         word_entry.serialize(&mut buffer).unwrap();
         assert_eq!(WordEntry::SERIALIZED_LEN, buffer.len());
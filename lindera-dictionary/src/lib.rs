@@ -1,66 +1,388 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
 use lindera_core::{
-    character_definition::CharacterDefinitions, connection::ConnectionCostMatrix,
-    dictionary::Dictionary, error::LinderaErrorKind, prefix_dict::PrefixDict,
-    unknown_dictionary::UnknownDictionary, LinderaResult,
+    character_definition::CharacterDefinitions,
+    connection::ConnectionCostMatrix,
+    dictionary::Dictionary,
+    dictionary_bundle::{DictionaryBundle, DICTIONARY_SECTIONS},
+    dictionary_data::DictionaryData,
+    dictionary_header::{read_data_with_header, read_with_header, DictionaryHeader},
+    dictionary_schema::DictionarySchema,
+    error::LinderaErrorKind,
+    prefix_dict::PrefixDict,
+    unknown_dictionary::UnknownDictionary,
+    LinderaResult,
 };
+use lindera_decompress::{decompress, CompressedData};
+
+/// Maps the file into memory so that processes loading the same dictionary share its pages.
+/// The file must not be modified while the dictionary is loaded.
+#[cfg(feature = "mmap")]
+fn read_data(path: PathBuf) -> LinderaResult<DictionaryData> {
+    let file = fs::File::open(path).map_err(|e| LinderaErrorKind::Io.with_error(e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| LinderaErrorKind::Io.with_error(e))?
+        .len();
+    if len == 0 {
+        // Empty files cannot be mapped on every platform.
+        return Ok(DictionaryData::Owned(Vec::new()));
+    }
+
+    // SAFETY: The mapping is read-only and lives as long as the dictionary data sharing it.
+    // Modifying or truncating the file while it is mapped is undefined behavior,
+    // so the dictionary files must not be modified by anyone while the dictionary is loaded.
+    let mmap =
+        unsafe { memmap2::Mmap::map(&file) }.map_err(|e| LinderaErrorKind::Io.with_error(e))?;
+    Ok(DictionaryData::shared(mmap))
+}
+
+#[cfg(not(feature = "mmap"))]
+fn read_data(path: PathBuf) -> LinderaResult<DictionaryData> {
+    fs::read(path)
+        .map(DictionaryData::Owned)
+        .map_err(|e| LinderaErrorKind::Io.with_error(e))
+}
 
-fn read_file(path: PathBuf) -> LinderaResult<Vec<u8>> {
-    fs::read(path).map_err(|e| LinderaErrorKind::Io.with_error(e))
+/// Reads the file, checks its header and decompresses the data if the file was built with compression.
+/// Uncompressed borrowed data, e.g. a memory-mapped file, stays borrowed.
+fn load_file(dir: &Path, filename: &str) -> LinderaResult<DictionaryData> {
+    load_file_with_header(dir, filename).map(|(_header, data)| data)
 }
 
+fn load_file_with_header(
+    dir: &Path,
+    filename: &str,
+) -> LinderaResult<(DictionaryHeader, DictionaryData)> {
+    let path = dir.join(filename);
+    let context = format!("failed to load {}", path.display());
+    read_data(path)
+        .and_then(read_data_with_header)
+        .and_then(|(header, data)| {
+            let data = decompress_data(&header, data)?;
+            Ok((header, data))
+        })
+        .map_err(|err| err.add_context(context))
+}
+
+fn decompress_data(
+    header: &DictionaryHeader,
+    data: DictionaryData,
+) -> LinderaResult<DictionaryData> {
+    if !header.compressed {
+        return Ok(data);
+    }
+
+    let compressed_data: CompressedData = bincode::deserialize(&data).map_err(|err| {
+        LinderaErrorKind::DictionaryLoadError.with_error(anyhow::anyhow!(
+            "invalid compressed {} dictionary data: {}",
+            header.kind,
+            err
+        ))
+    })?;
+    decompress(compressed_data)
+        .map(DictionaryData::Owned)
+        .map_err(|err| LinderaErrorKind::DictionaryLoadError.with_error(err))
+}
+
+/// Loads the dictionary from the directory, or from the file if the path is a dictionary bundle.
 pub fn load_dictionary(path: PathBuf) -> LinderaResult<Dictionary> {
+    if path.is_file() {
+        return load_bundle(path);
+    }
+
+    // The kind of the dictionary is taken from the header of the double array.
+    let (header, da_data) = load_file_with_header(&path, "dict.da")?;
+    let vals_data = load_file(&path, "dict.vals")?;
+
     Ok(Dictionary {
-        dict: prefix_dict(path.clone())?,
+        dict: PrefixDict::from_data(da_data, vals_data),
         cost_matrix: connection(path.clone())?,
         char_definitions: char_def(path.clone())?,
         unknown_dictionary: unknown_dict(path.clone())?,
         words_idx_data: words_idx_data(path.clone())?,
-        words_data: words_data(path)?,
+        words_data: words_data(path.clone())?,
+        kind: header.kind.parse().ok(),
+        schema: schema(path)?,
     })
 }
 
 pub fn char_def(dir: PathBuf) -> LinderaResult<CharacterDefinitions> {
-    let path = dir.join("char_def.bin");
-    let data = read_file(path)?;
+    let data = load_file(&dir, "char_def.bin")?;
 
-    CharacterDefinitions::load(data.as_slice())
+    CharacterDefinitions::load(&data)
 }
 
 pub fn connection(dir: PathBuf) -> LinderaResult<ConnectionCostMatrix> {
-    let path = dir.join("matrix.mtx");
-    let data = read_file(path)?;
+    let data = load_file(&dir, "matrix.mtx")?;
 
-    Ok(ConnectionCostMatrix::load(data.as_slice()))
+    Ok(ConnectionCostMatrix::from_data(data))
 }
 
 pub fn prefix_dict(dir: PathBuf) -> LinderaResult<PrefixDict> {
-    let unidic_data_path = dir.join("dict.da");
-    let unidic_data = read_file(unidic_data_path)?;
-
-    let unidic_vals_path = dir.join("dict.vals");
-    let unidic_vals = read_file(unidic_vals_path)?;
+    let unidic_data = load_file(&dir, "dict.da")?;
+    let unidic_vals = load_file(&dir, "dict.vals")?;
 
-    Ok(PrefixDict::from_static_slice(
-        unidic_data.as_slice(),
-        unidic_vals.as_slice(),
-    ))
+    Ok(PrefixDict::from_data(unidic_data, unidic_vals))
 }
 
 pub fn unknown_dict(dir: PathBuf) -> LinderaResult<UnknownDictionary> {
-    let path = dir.join("unk.bin");
-    let data = read_file(path)?;
+    let data = load_file(&dir, "unk.bin")?;
+
+    UnknownDictionary::load(&data)
+}
 
-    UnknownDictionary::load(data.as_slice())
+pub fn words_idx_data(dir: PathBuf) -> LinderaResult<DictionaryData> {
+    load_file(&dir, "dict.wordsidx")
 }
 
-pub fn words_idx_data(dir: PathBuf) -> LinderaResult<Vec<u8>> {
-    let path = dir.join("dict.wordsidx");
-    read_file(path)
+pub fn words_data(dir: PathBuf) -> LinderaResult<DictionaryData> {
+    load_file(&dir, "dict.words")
 }
 
-pub fn words_data(dir: PathBuf) -> LinderaResult<Vec<u8>> {
-    let path = dir.join("dict.words");
-    read_file(path)
+pub fn schema(dir: PathBuf) -> LinderaResult<DictionarySchema> {
+    let data = load_file(&dir, "schema.bin")?;
+
+    DictionarySchema::load(&data)
+}
+
+/// Loads the dictionary from the single-file bundle.
+pub fn load_bundle(path: PathBuf) -> LinderaResult<Dictionary> {
+    let context = format!("failed to load {}", path.display());
+    read_data(path)
+        .and_then(Dictionary::from_data)
+        .map_err(|err| err.add_context(context))
+}
+
+/// Checks the checksums of the dictionary files in the directory, or of the dictionary bundle.
+/// Memory-mapped dictionaries only have their lengths checked on load,
+/// since checking the checksums reads the whole files.
+///
+/// # Arguments
+///
+/// * `path`: The directory of the dictionary files or the bundle file.
+///
+/// returns: Result<(), LinderaError>
+///
+pub fn verify_dictionary(path: PathBuf) -> LinderaResult<()> {
+    let files = if path.is_file() {
+        vec![path]
+    } else {
+        DICTIONARY_SECTIONS
+            .iter()
+            .map(|name| path.join(name))
+            .collect()
+    };
+    for file in files {
+        let context = format!("failed to verify {}", file.display());
+        fs::read(&file)
+            .map_err(|e| LinderaErrorKind::Io.with_error(e))
+            .and_then(|data| read_with_header(&data).map(|_| ()))
+            .map_err(|err| err.add_context(context))?;
+    }
+
+    Ok(())
+}
+
+/// Bundles the dictionary files in the directory into a single file.
+/// Compressed files are stored decompressed so that the bundle can be memory-mapped.
+///
+/// # Arguments
+///
+/// * `dir`: The directory of the dictionary files.
+/// * `output_file`: The bundle file to write.
+///
+/// returns: Result<(), LinderaError>
+///
+pub fn write_bundle(dir: &Path, output_file: &Path) -> LinderaResult<()> {
+    let mut headers = Vec::with_capacity(DICTIONARY_SECTIONS.len());
+    let mut sections = Vec::with_capacity(DICTIONARY_SECTIONS.len());
+    for name in DICTIONARY_SECTIONS {
+        let (header, data) = load_file_with_header(dir, name)?;
+        headers.push(header);
+        sections.push((name, data));
+    }
+    let header = &headers[0];
+    if let Some(other) = headers.iter().find(|other| other.kind != header.kind) {
+        return Err(
+            LinderaErrorKind::DictionaryBuildError.with_error(anyhow::anyhow!(
+                "the files in {} belong to different dictionaries: {} and {}",
+                dir.display(),
+                header.kind,
+                other.kind
+            )),
+        );
+    }
+
+    let sections: Vec<(&str, &[u8])> = sections
+        .iter()
+        .map(|(name, data)| (*name, data.as_ref()))
+        .collect();
+    let mut wtr = io::BufWriter::new(
+        fs::File::create(output_file)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
+    );
+    DictionaryBundle::write(&mut wtr, &header.kind, &header.builder_version, &sections)?;
+    wtr.flush()
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use lindera_compress::{compress, Algorithm};
+    use lindera_core::{
+        dictionary::DictionaryKind,
+        dictionary_builder::DictionaryBuilder,
+        dictionary_header::{read_with_header, write_with_header},
+        error::LinderaErrorKind,
+    };
+    use lindera_unidic_builder::unidic_builder::UnidicBuilder;
+
+    use crate::{load_bundle, load_dictionary, verify_dictionary, write_bundle};
+
+    fn build_mini_dictionary(output_dir: &std::path::Path) {
+        let input_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../resources")
+            .join("unidic_mini_dictionary");
+        UnidicBuilder::new()
+            .build_dictionary(&input_dir, output_dir)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_load_dictionary_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        build_mini_dictionary(dir.path());
+
+        let dictionary = load_dictionary(dir.path().to_path_buf()).unwrap();
+        assert_eq!(dictionary.kind, Some(DictionaryKind::UniDic));
+        assert_eq!(dictionary.schema.name, "unidic");
+        assert_eq!(dictionary.schema.fields[0], "surface");
+        // Memory-mapped files are shared, not copied.
+        assert_eq!(dictionary.words_data.is_borrowed(), cfg!(feature = "mmap"));
+        assert_eq!(
+            dictionary.words_idx_data.is_borrowed(),
+            cfg!(feature = "mmap")
+        );
+
+        let prefixes = dictionary
+            .dict
+            .prefix("羽田空港")
+            .map(|(prefix_len, _)| prefix_len)
+            .collect::<Vec<_>>();
+        assert_eq!(prefixes, vec![6, 12]);
+    }
+
+    #[test]
+    fn test_load_dictionary_from_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        build_mini_dictionary(dir.path());
+        let bundle_file = dir.path().join("mini.lindera");
+        write_bundle(dir.path(), &bundle_file).unwrap();
+
+        for dictionary in [
+            load_bundle(bundle_file.clone()).unwrap(),
+            load_dictionary(bundle_file.clone()).unwrap(),
+        ] {
+            assert_eq!(dictionary.kind, Some(DictionaryKind::UniDic));
+            assert_eq!(dictionary.schema.name, "unidic");
+            assert_eq!(dictionary.words_data.is_borrowed(), cfg!(feature = "mmap"));
+            assert_eq!(
+                dictionary
+                    .dict
+                    .prefix("羽田空港")
+                    .map(|(prefix_len, _)| prefix_len)
+                    .collect::<Vec<_>>(),
+                vec![6, 12]
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_dictionary_without_header() {
+        let dir = tempfile::tempdir().unwrap();
+        build_mini_dictionary(dir.path());
+        fs::write(dir.path().join("matrix.mtx"), b"no header").unwrap();
+
+        let err = load_dictionary(dir.path().to_path_buf()).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+        assert!(err
+            .to_string()
+            .contains(&dir.path().join("matrix.mtx").display().to_string()));
+    }
+
+    #[test]
+    fn test_verify_dictionary() {
+        let dir = tempfile::tempdir().unwrap();
+        build_mini_dictionary(dir.path());
+        let bundle_file = dir.path().join("mini.lindera");
+        write_bundle(dir.path(), &bundle_file).unwrap();
+        verify_dictionary(dir.path().to_path_buf()).unwrap();
+        verify_dictionary(bundle_file).unwrap();
+
+        let words_file = dir.path().join("dict.words");
+        let mut words = fs::read(&words_file).unwrap();
+        *words.last_mut().unwrap() ^= 1;
+        fs::write(&words_file, words).unwrap();
+
+        // Only the lengths of memory-mapped files are checked on load.
+        assert_eq!(
+            load_dictionary(dir.path().to_path_buf()).is_ok(),
+            cfg!(feature = "mmap")
+        );
+        let err = verify_dictionary(dir.path().to_path_buf()).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert!(err.to_string().contains(&words_file.display().to_string()));
+    }
+
+    #[test]
+    fn test_load_compressed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        build_mini_dictionary(dir.path());
+        let words_file = dir.path().join("dict.words");
+        let words = fs::read(&words_file).unwrap();
+        let (header, words) = read_with_header(&words).unwrap();
+        assert!(!header.compressed);
+
+        let compressed_data = compress(words, Algorithm::Deflate).unwrap();
+        let compressed = bincode::serialize(&compressed_data).unwrap();
+        let mut buffer = Vec::new();
+        write_with_header(
+            &mut buffer,
+            &header.kind,
+            &header.builder_version,
+            true,
+            &compressed,
+        )
+        .unwrap();
+        fs::write(&words_file, buffer).unwrap();
+
+        let dictionary = load_dictionary(dir.path().to_path_buf()).unwrap();
+        // Decompressed data is always a copy.
+        assert!(!dictionary.words_data.is_borrowed());
+        assert_eq!(&dictionary.words_data[..], words);
+    }
+
+    #[test]
+    fn test_load_corrupted_compressed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        build_mini_dictionary(dir.path());
+        let words_file = dir.path().join("dict.words");
+        let mut buffer = Vec::new();
+        write_with_header(&mut buffer, "unidic", "0.19.1", true, b"not compressed").unwrap();
+        fs::write(&words_file, buffer).unwrap();
+
+        let err = load_dictionary(dir.path().to_path_buf()).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::DictionaryLoadError);
+        let message = err.to_string();
+        assert!(message.contains(&words_file.display().to_string()));
+        assert!(message.contains("invalid compressed unidic dictionary data"));
+    }
 }
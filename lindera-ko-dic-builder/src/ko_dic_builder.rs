@@ -18,9 +18,12 @@ use lindera_compress::compress;
 use lindera_core::{
     character_definition::{CharacterDefinitions, CharacterDefinitionsBuilder},
     dictionary_builder::DictionaryBuilder,
+    dictionary_header::write_with_header,
+    dictionary_schema::{write_schema, DictionarySchema, COMMON_FIELDS},
     error::LinderaErrorKind,
     file_util::read_utf8_file,
     prefix_dict::PrefixDict,
+    token_features::KoDicFeatures,
     unknown_dictionary::parse_unk,
     user_dictionary::UserDictionary,
     word_entry::{WordEntry, WordId},
@@ -31,8 +34,10 @@ use lindera_decompress::Algorithm;
 const SIMPLE_USERDIC_FIELDS_NUM: usize = 3;
 const SIMPLE_WORD_COST: i16 = -10000;
 const SIMPLE_CONTEXT_ID: u16 = 0;
-const DETAILED_USERDIC_FIELDS_NUM: usize = 12;
+const DETAILED_USERDIC_FIELDS_NUM: usize = COMMON_FIELDS.len() + KoDicFeatures::FIELDS.len();
 const COMPRESS_ALGORITHM: Algorithm = Algorithm::Deflate;
+const DICTIONARY_KIND: &str = "ko-dic";
+const BUILDER_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub struct KoDicBuilder {}
 
@@ -59,6 +64,7 @@ impl DictionaryBuilder for KoDicBuilder {
         self.build_unk(input_dir, &chardef, output_dir).unwrap();
         self.build_dict(input_dir, output_dir).unwrap();
         self.build_cost_matrix(input_dir, output_dir).unwrap();
+        self.build_schema(output_dir).unwrap();
 
         Ok(())
     }
@@ -81,8 +87,15 @@ impl DictionaryBuilder for KoDicBuilder {
             File::create(output_file)
                 .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
         );
-        bincode::serialize_into(&mut wtr, &user_dict)
+        let user_dict_buffer = bincode::serialize(&user_dict)
             .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+        write_with_header(
+            &mut wtr,
+            DICTIONARY_KIND,
+            BUILDER_VERSION,
+            false,
+            &user_dict_buffer,
+        )?;
         wtr.flush()
             .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
 
@@ -131,7 +144,12 @@ impl DictionaryBuilder for KoDicBuilder {
         debug!("reading {:?}", unk_data_path);
 
         let unk_data = read_utf8_file(&unk_data_path)?;
-        let unknown_dictionary = parse_unk(chardef.categories(), &unk_data, Self::UNK_FIELDS_NUM)?;
+        let unknown_dictionary = parse_unk(
+            chardef.categories(),
+            &unk_data,
+            Self::UNK_FIELDS_NUM,
+            KoDicFeatures::FIELDS.len(),
+        )?;
 
         let mut unk_buffer = Vec::new();
         bincode::serialize_into(&mut unk_buffer, &unknown_dictionary)
@@ -220,9 +238,13 @@ impl DictionaryBuilder for KoDicBuilder {
                         LinderaErrorKind::Parse
                             .with_error(anyhow::anyhow!("failed to parse word_cost"))
                     })?,
-                    cost_id: u16::from_str(row[1].trim()).map_err(|_err| {
+                    left_id: u16::from_str(row[1].trim()).map_err(|_err| {
                         LinderaErrorKind::Parse
-                            .with_error(anyhow::anyhow!("failed to parse cost_id"))
+                            .with_error(anyhow::anyhow!("failed to parse left_id"))
+                    })?,
+                    right_id: u16::from_str(row[2].trim()).map_err(|_err| {
+                        LinderaErrorKind::Parse
+                            .with_error(anyhow::anyhow!("failed to parse right_id"))
                     })?,
                 });
         }
@@ -377,13 +399,19 @@ impl DictionaryBuilder for KoDicBuilder {
                     LinderaErrorKind::Parse.with_error(anyhow::anyhow!("failed to parse word cost"))
                 })?
             };
-            let cost_id = if row.len() == SIMPLE_USERDIC_FIELDS_NUM {
-                SIMPLE_CONTEXT_ID
+            let (left_id, right_id) = if row.len() == SIMPLE_USERDIC_FIELDS_NUM {
+                (SIMPLE_CONTEXT_ID, SIMPLE_CONTEXT_ID)
             } else {
-                row[1].parse::<u16>().map_err(|_err| {
-                    LinderaErrorKind::Parse
-                        .with_error(anyhow::anyhow!("failed to parse left context id"))
-                })?
+                (
+                    row[1].parse::<u16>().map_err(|_err| {
+                        LinderaErrorKind::Parse
+                            .with_error(anyhow::anyhow!("failed to parse left context id"))
+                    })?,
+                    row[2].parse::<u16>().map_err(|_err| {
+                        LinderaErrorKind::Parse
+                            .with_error(anyhow::anyhow!("failed to parse right context id"))
+                    })?,
+                )
             };
 
             word_entry_map
@@ -392,7 +420,8 @@ impl DictionaryBuilder for KoDicBuilder {
                 .push(WordEntry {
                     word_id: WordId(row_id as u32, true),
                     word_cost,
-                    cost_id,
+                    left_id,
+                    right_id,
                 });
         }
 
@@ -457,8 +486,8 @@ impl DictionaryBuilder for KoDicBuilder {
         }
 
         let dict = PrefixDict {
-            da: DoubleArray::new(da_bytes),
-            vals_data,
+            da: DoubleArray::new(da_bytes.into()),
+            vals_data: vals_data.into(),
             is_system: false,
         };
 
@@ -468,6 +497,16 @@ impl DictionaryBuilder for KoDicBuilder {
             words_data,
         })
     }
+
+    fn schema(&self) -> DictionarySchema {
+        DictionarySchema::new(DICTIONARY_KIND, &KoDicFeatures::FIELDS)
+    }
+
+    fn build_schema(&self, output_dir: &Path) -> LinderaResult<()> {
+        write_schema(&self.schema(), output_dir, |buffer, writer| {
+            compress_write(buffer, COMPRESS_ALGORITHM, writer)
+        })
+    }
 }
 
 #[cfg(feature = "compress")]
@@ -478,10 +517,16 @@ fn compress_write<W: Write>(
 ) -> LinderaResult<()> {
     let compressed = compress(buffer, algorithm)
         .map_err(|err| LinderaErrorKind::Compress.with_error(anyhow::anyhow!(err)))?;
-    bincode::serialize_into(writer, &compressed)
-        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
-
-    Ok(())
+    let compressed_buffer = bincode::serialize(&compressed)
+        .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+
+    write_with_header(
+        writer,
+        DICTIONARY_KIND,
+        BUILDER_VERSION,
+        true,
+        &compressed_buffer,
+    )
 }
 
 #[cfg(not(feature = "compress"))]
@@ -490,9 +535,5 @@ fn compress_write<W: Write>(
     _algorithm: Algorithm,
     writer: &mut W,
 ) -> LinderaResult<()> {
-    writer
-        .write_all(buffer)
-        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
-
-    Ok(())
+    write_with_header(writer, DICTIONARY_KIND, BUILDER_VERSION, false, buffer)
 }
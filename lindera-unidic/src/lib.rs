@@ -2,124 +2,121 @@
 use std::env;
 
 use lindera_core::{
-    character_definition::CharacterDefinitions, connection::ConnectionCostMatrix,
-    dictionary::Dictionary, prefix_dict::PrefixDict, unknown_dictionary::UnknownDictionary,
+    character_definition::CharacterDefinitions,
+    connection::ConnectionCostMatrix,
+    dictionary::{Dictionary, DictionaryKind},
+    dictionary_data::DictionaryData,
+    dictionary_header::read_with_header,
+    dictionary_schema::DictionarySchema,
+    error::LinderaErrorKind,
+    prefix_dict::PrefixDict,
+    unknown_dictionary::UnknownDictionary,
     LinderaResult,
 };
 #[cfg(feature = "compress")]
 use lindera_decompress::decompress;
 
+// The data is a `static` so that it is decompressed at most once per process
+// and the dictionaries borrow it instead of copying it.
+// The header of the file is checked once as well, and a failure is kept to be reported on each load.
+// Without the `unidic` feature, the data is empty and fails to load.
 macro_rules! decompress_data {
-    ($name: ident, $bytes: expr, $filename: literal) => {
+    ($name: ident, $filename: literal) => {
         #[cfg(feature = "compress")]
-        const $name: once_cell::sync::Lazy<Vec<u8>> = once_cell::sync::Lazy::new(|| {
-            let compressed_data = bincode::deserialize_from(&$bytes[..])
-                .expect(concat!("invalid file format ", $filename));
-            decompress(compressed_data).expect(concat!("invalid file format ", $filename))
-        });
+        static $name: once_cell::sync::Lazy<Result<Vec<u8>, String>> =
+            once_cell::sync::Lazy::new(|| {
+                let (_header, data) = read_with_header(embedded_bytes!($filename))
+                    .map_err(|err| invalid_data($filename, err))?;
+                let compressed_data =
+                    bincode::deserialize_from(data).map_err(|err| invalid_data($filename, err))?;
+                decompress(compressed_data).map_err(|err| invalid_data($filename, err))
+            });
         #[cfg(not(feature = "compress"))]
-        const $name: &'static [u8] = $bytes;
+        static $name: once_cell::sync::Lazy<Result<&[u8], String>> =
+            once_cell::sync::Lazy::new(|| {
+                read_with_header(embedded_bytes!($filename))
+                    .map(|(_header, data)| data)
+                    .map_err(|err| invalid_data($filename, err))
+            });
     };
 }
 
 #[cfg(feature = "unidic")]
-decompress_data!(
-    CHAR_DEFINITION_DATA,
-    include_bytes!(concat!(env!("OUT_DIR"), "/lindera-unidic/char_def.bin")),
-    "char_def.bin"
-);
-#[cfg(not(feature = "unidic"))]
-decompress_data!(CHAR_DEFINITION_DATA, &[], "char_def.bin");
-
-#[cfg(feature = "unidic")]
-decompress_data!(
-    CONNECTION_DATA,
-    include_bytes!(concat!(env!("OUT_DIR"), "/lindera-unidic/matrix.mtx")),
-    "matrix.mtx"
-);
-#[cfg(not(feature = "unidic"))]
-decompress_data!(CONNECTION_DATA, &[], "matrix.mtx");
-
-#[cfg(feature = "unidic")]
-decompress_data!(
-    UNIDIC_DATA,
-    include_bytes!(concat!(env!("OUT_DIR"), "/lindera-unidic/dict.da")),
-    "dict.da"
-);
-#[cfg(not(feature = "unidic"))]
-decompress_data!(UNIDIC_DATA, &[], "dict.da");
+macro_rules! embedded_bytes {
+    ($filename: literal) => {
+        &include_bytes!(concat!(env!("OUT_DIR"), "/lindera-unidic/", $filename))[..]
+    };
+}
 
-#[cfg(feature = "unidic")]
-decompress_data!(
-    UNIDIC_VALS,
-    include_bytes!(concat!(env!("OUT_DIR"), "/lindera-unidic/dict.vals")),
-    "dict.vals"
-);
 #[cfg(not(feature = "unidic"))]
-decompress_data!(UNIDIC_VALS, &[], "dict.vals");
+macro_rules! embedded_bytes {
+    ($filename: literal) => {
+        &[]
+    };
+}
 
-#[cfg(feature = "unidic")]
-decompress_data!(
-    UNKNOWN_DATA,
-    include_bytes!(concat!(env!("OUT_DIR"), "/lindera-unidic/unk.bin")),
-    "unk.bin"
-);
-#[cfg(not(feature = "unidic"))]
-decompress_data!(UNKNOWN_DATA, &[], "unk.bin");
+fn invalid_data<E: std::fmt::Display>(filename: &str, err: E) -> String {
+    format!("invalid embedded {}: {}", filename, err)
+}
 
-#[cfg(feature = "unidic")]
-decompress_data!(
-    WORDS_IDX_DATA,
-    include_bytes!(concat!(env!("OUT_DIR"), "/lindera-unidic/dict.wordsidx")),
-    "dict.wordsidx"
-);
-#[cfg(not(feature = "unidic"))]
-decompress_data!(WORDS_IDX_DATA, &[], "dict.wordsidx");
+fn embedded_data<T>(data: &'static Result<T, String>) -> LinderaResult<&'static [u8]>
+where
+    T: AsRef<[u8]>,
+{
+    data.as_ref().map(|data| data.as_ref()).map_err(|err| {
+        LinderaErrorKind::DictionaryLoadError.with_error(anyhow::anyhow!(err.clone()))
+    })
+}
 
-#[cfg(feature = "unidic")]
-decompress_data!(
-    WORDS_DATA,
-    include_bytes!(concat!(env!("OUT_DIR"), "/lindera-unidic/dict.words")),
-    "dict.words"
-);
-#[cfg(not(feature = "unidic"))]
-decompress_data!(WORDS_DATA, &[], "dict.words");
+decompress_data!(CHAR_DEFINITION_DATA, "char_def.bin");
+decompress_data!(CONNECTION_DATA, "matrix.mtx");
+decompress_data!(UNIDIC_DATA, "dict.da");
+decompress_data!(UNIDIC_VALS, "dict.vals");
+decompress_data!(UNKNOWN_DATA, "unk.bin");
+decompress_data!(WORDS_IDX_DATA, "dict.wordsidx");
+decompress_data!(WORDS_DATA, "dict.words");
+decompress_data!(SCHEMA_DATA, "schema.bin");
 
 pub fn load_dictionary() -> LinderaResult<Dictionary> {
     Ok(Dictionary {
-        dict: prefix_dict(),
-        cost_matrix: connection(),
+        dict: prefix_dict()?,
+        cost_matrix: connection()?,
         char_definitions: char_def()?,
         unknown_dictionary: unknown_dict()?,
-        words_idx_data: words_idx_data(),
-        words_data: words_data(),
+        words_idx_data: words_idx_data()?,
+        words_data: words_data()?,
+        schema: schema()?,
+        kind: Some(DictionaryKind::UniDic),
     })
 }
 
 pub fn char_def() -> LinderaResult<CharacterDefinitions> {
-    #[allow(clippy::needless_borrow)]
-    CharacterDefinitions::load(&CHAR_DEFINITION_DATA)
+    CharacterDefinitions::load(embedded_data(&CHAR_DEFINITION_DATA)?)
 }
 
-pub fn connection() -> ConnectionCostMatrix {
-    #[allow(clippy::needless_borrow)]
-    ConnectionCostMatrix::load(&CONNECTION_DATA)
+pub fn connection() -> LinderaResult<ConnectionCostMatrix> {
+    let data = embedded_data(&CONNECTION_DATA)?;
+    Ok(ConnectionCostMatrix::load_static(data))
 }
 
-pub fn prefix_dict() -> PrefixDict {
-    #[allow(clippy::needless_borrow)]
-    PrefixDict::from_static_slice(&UNIDIC_DATA, &UNIDIC_VALS)
+pub fn prefix_dict() -> LinderaResult<PrefixDict> {
+    let da_data = embedded_data(&UNIDIC_DATA)?;
+    let vals_data = embedded_data(&UNIDIC_VALS)?;
+    Ok(PrefixDict::from_static_slice(da_data, vals_data))
 }
 
 pub fn unknown_dict() -> LinderaResult<UnknownDictionary> {
-    #[allow(clippy::needless_borrow)]
-    UnknownDictionary::load(&UNKNOWN_DATA)
+    UnknownDictionary::load(embedded_data(&UNKNOWN_DATA)?)
+}
+
+pub fn words_idx_data() -> LinderaResult<DictionaryData> {
+    embedded_data(&WORDS_IDX_DATA).map(DictionaryData::Static)
 }
 
-pub fn words_idx_data() -> Vec<u8> {
-    WORDS_IDX_DATA.to_vec()
+pub fn words_data() -> LinderaResult<DictionaryData> {
+    embedded_data(&WORDS_DATA).map(DictionaryData::Static)
 }
 
-pub fn words_data() -> Vec<u8> {
-    WORDS_DATA.to_vec()
+pub fn schema() -> LinderaResult<DictionarySchema> {
+    DictionarySchema::load(embedded_data(&SCHEMA_DATA)?)
 }
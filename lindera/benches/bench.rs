@@ -24,6 +24,21 @@ use lindera::{
     DictionaryKind,
 };
 
+#[cfg(any(feature = "ipadic", feature = "unidic"))]
+use lindera::tokenizer::TokenizerWorkspace;
+
+#[cfg(any(feature = "ipadic", feature = "unidic"))]
+const SHORT_QUERIES: &[&str] = &[
+    "東京スカイツリー",
+    "関西国際空港",
+    "すもももももももものうち",
+    "渋谷 ラーメン",
+    "新宿駅 近く カフェ",
+    "明日の天気",
+    "日本語の形態素解析",
+    "羽田空港 アクセス",
+];
+
 #[allow(unused_variables)]
 fn bench_constructor(c: &mut Criterion) {
     #[cfg(feature = "ipadic")]
@@ -39,6 +54,7 @@ fn bench_constructor(c: &mut Criterion) {
                     dictionary,
                     user_dictionary: None,
                     mode: Mode::Normal,
+                    ..TokenizerConfig::default()
                 };
                 Tokenizer::with_config(config).unwrap()
             })
@@ -58,6 +74,7 @@ fn bench_constructor(c: &mut Criterion) {
                     dictionary,
                     user_dictionary: None,
                     mode: Mode::Normal,
+                    ..TokenizerConfig::default()
                 };
                 Tokenizer::with_config(config).unwrap()
             })
@@ -77,6 +94,7 @@ fn bench_constructor(c: &mut Criterion) {
                     dictionary,
                     user_dictionary: None,
                     mode: Mode::Normal,
+                    ..TokenizerConfig::default()
                 };
                 Tokenizer::with_config(config).unwrap()
             })
@@ -96,6 +114,7 @@ fn bench_constructor(c: &mut Criterion) {
                     dictionary,
                     user_dictionary: None,
                     mode: Mode::Normal,
+                    ..TokenizerConfig::default()
                 };
                 Tokenizer::with_config(config).unwrap()
             })
@@ -127,6 +146,7 @@ fn bench_constructor_with_simple_userdic(c: &mut Criterion) {
                     dictionary,
                     user_dictionary,
                     mode: Mode::Normal,
+                    ..TokenizerConfig::default()
                 };
                 Tokenizer::with_config(config).unwrap()
             })
@@ -155,6 +175,7 @@ fn bench_constructor_with_simple_userdic(c: &mut Criterion) {
                     dictionary,
                     user_dictionary,
                     mode: Mode::Normal,
+                    ..TokenizerConfig::default()
                 };
                 Tokenizer::with_config(config).unwrap()
             })
@@ -183,6 +204,7 @@ fn bench_constructor_with_simple_userdic(c: &mut Criterion) {
                     dictionary,
                     user_dictionary,
                     mode: Mode::Normal,
+                    ..TokenizerConfig::default()
                 };
                 Tokenizer::with_config(config).unwrap()
             })
@@ -211,6 +233,7 @@ fn bench_constructor_with_simple_userdic(c: &mut Criterion) {
                     dictionary,
                     user_dictionary,
                     mode: Mode::Normal,
+                    ..TokenizerConfig::default()
                 };
                 Tokenizer::with_config(config).unwrap()
             })
@@ -231,6 +254,7 @@ fn bench_tokenize(c: &mut Criterion) {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -251,6 +275,7 @@ fn bench_tokenize(c: &mut Criterion) {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -271,6 +296,7 @@ fn bench_tokenize(c: &mut Criterion) {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -291,6 +317,7 @@ fn bench_tokenize(c: &mut Criterion) {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -323,6 +350,7 @@ fn bench_tokenize_with_simple_userdic(c: &mut Criterion) {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -353,6 +381,7 @@ fn bench_tokenize_with_simple_userdic(c: &mut Criterion) {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -383,6 +412,7 @@ fn bench_tokenize_with_simple_userdic(c: &mut Criterion) {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -411,6 +441,7 @@ fn bench_tokenize_with_simple_userdic(c: &mut Criterion) {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -453,6 +484,7 @@ fn bench_tokenize_long_text(c: &mut Criterion) {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
         let tokenizer = Tokenizer::with_config(config).unwrap();
 
@@ -496,6 +528,7 @@ fn bench_tokenize_long_text(c: &mut Criterion) {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
         let tokenizer = Tokenizer::with_config(config).unwrap();
 
@@ -542,6 +575,7 @@ fn bench_tokenize_details_long_text(c: &mut Criterion) {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
         let tokenizer = Tokenizer::with_config(config).unwrap();
 
@@ -585,6 +619,7 @@ fn bench_tokenize_details_long_text(c: &mut Criterion) {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
         let tokenizer = Tokenizer::with_config(config).unwrap();
 
@@ -598,6 +633,85 @@ fn bench_tokenize_details_long_text(c: &mut Criterion) {
     }
 }
 
+#[allow(unused_variables)]
+fn bench_tokenize_into(c: &mut Criterion) {
+    #[cfg(feature = "ipadic")]
+    {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+
+        // Compare allocating a new lattice for each query with reusing a workspace
+        let mut group = c.benchmark_group("tokenize-short-queries-ipadic");
+        group.bench_function("bench-tokenize-short-queries-ipadic", |b| {
+            b.iter(|| {
+                for query in SHORT_QUERIES {
+                    tokenizer.tokenize(query).unwrap();
+                }
+            })
+        });
+        group.bench_function("bench-tokenize-into-short-queries-ipadic", |b| {
+            let mut workspace = TokenizerWorkspace::new();
+            let mut tokens = Vec::new();
+            b.iter(|| {
+                for query in SHORT_QUERIES {
+                    tokenizer
+                        .tokenize_into(&mut workspace, query, &mut tokens)
+                        .unwrap();
+                }
+            })
+        });
+        group.finish();
+    }
+
+    #[cfg(feature = "unidic")]
+    {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::UniDic),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+
+        // Compare allocating a new lattice for each query with reusing a workspace
+        let mut group = c.benchmark_group("tokenize-short-queries-unidic");
+        group.bench_function("bench-tokenize-short-queries-unidic", |b| {
+            b.iter(|| {
+                for query in SHORT_QUERIES {
+                    tokenizer.tokenize(query).unwrap();
+                }
+            })
+        });
+        group.bench_function("bench-tokenize-into-short-queries-unidic", |b| {
+            let mut workspace = TokenizerWorkspace::new();
+            let mut tokens = Vec::new();
+            b.iter(|| {
+                for query in SHORT_QUERIES {
+                    tokenizer
+                        .tokenize_into(&mut workspace, query, &mut tokens)
+                        .unwrap();
+                }
+            })
+        });
+        group.finish();
+    }
+}
+
 criterion_group!(
     benches,
     bench_constructor,
@@ -606,5 +720,6 @@ criterion_group!(
     bench_tokenize_with_simple_userdic,
     bench_tokenize_long_text,
     bench_tokenize_details_long_text,
+    bench_tokenize_into,
 );
 criterion_main!(benches);
@@ -28,6 +28,7 @@ fn main() -> LinderaResult<()> {
             dictionary,
             user_dictionary: user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         #[allow(unused_variables)]
@@ -18,6 +18,7 @@ fn main() -> LinderaResult<()> {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         #[allow(unused_variables)]
@@ -4,6 +4,7 @@ use serde_json::Value;
 
 use lindera_core::{
     character_filter::{correct_offset, CharacterFilter},
+    token::set_char_offsets,
     token_filter::TokenFilter,
 };
 
@@ -297,6 +298,13 @@ impl Analyzer {
                 details: token.details.clone(),
                 byte_start: token.byte_start,
                 byte_end: token.byte_end,
+                confidence: token.confidence,
+                dictionary_kind: token.dictionary_kind.clone(),
+                schema: token.schema.clone(),
+                metadata: token.metadata,
+                position: token.position,
+                position_length: token.position_length,
+                ..Default::default()
             };
 
             for (i, offsets) in offsets_vec.iter().enumerate() {
@@ -312,9 +320,29 @@ impl Analyzer {
 
             tokens.push(new_token);
         }
+        set_char_offsets(text, &mut tokens);
 
         Ok(tokens)
     }
+
+    /// Analyzes each of the texts and returns the tokens in the input order.
+    /// With the `parallel` feature, the texts are analyzed on the rayon thread pool.
+    pub fn analyze_batch<'a>(
+        &self,
+        texts: &[&'a str],
+    ) -> crate::LinderaResult<Vec<Vec<crate::Token<'a>>>> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            texts.par_iter().map(|text| self.analyze(text)).collect()
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            texts.iter().map(|text| self.analyze(text)).collect()
+        }
+    }
 }
 
 impl Clone for Analyzer {
@@ -605,7 +633,20 @@ mod tests {
                 let end = token.byte_end;
                 assert_eq!(token.text, "Lindera");
                 assert_eq!(&text[start..end], "ﾘﾝﾃﾞﾗ");
+                assert_eq!((token.char_start, token.char_end), (Some(0), Some(5)));
+                assert_eq!((token.utf16_start, token.utf16_end), (Some(0), Some(5)));
             }
+            {
+                let token = tokens_iter.next().unwrap();
+                assert_eq!(token.text, "形態素");
+                assert_eq!((token.char_start, token.char_end), (Some(6), Some(9)));
+            }
+
+            // The position of "は", removed by the stop tags, is left as a gap.
+            assert_eq!(
+                tokens.iter().map(|t| t.position).collect::<Vec<_>>(),
+                vec![Some(0), Some(2), Some(3), Some(4)]
+            );
         }
 
         {
@@ -662,6 +703,103 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_ipadic_analyzer_sentence_splitter() {
+        let config_str = r#"
+        {
+            "character_filters": [],
+            "tokenizer": {
+                "dictionary": {
+                    "kind": "ipadic"
+                },
+                "mode": "normal",
+                "sentence_splitter": {
+                    "kind": "rule_based",
+                    "args": {
+                        "terminators": ["。"],
+                        "brackets": [["「", "」"]]
+                    }
+                }
+            },
+            "token_filters": []
+        }
+        "#;
+        let analyzer = Analyzer::from_slice(config_str.as_bytes()).unwrap();
+
+        let text = "「東京へ行く。」と言った。".to_string();
+        let sentence_splitter = analyzer.tokenizer.sentence_splitter();
+        assert_eq!(sentence_splitter.name(), "rule_based");
+        // The terminator inside the brackets does not end a sentence, the others do.
+        assert_eq!(sentence_splitter.split(&text), vec![0..text.len()]);
+        assert_eq!(
+            sentence_splitter.split("東京へ行く。大阪へ行く。"),
+            vec![0..18, 18..36]
+        );
+
+        let mut analyze_text = text.clone();
+        let tokens = analyzer.analyze(&mut analyze_text).unwrap();
+        for token in tokens.iter() {
+            assert_eq!(&text[token.byte_start..token.byte_end], token.text);
+        }
+        assert_eq!(
+            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
+            vec!["「", "東京", "へ", "行く", "。", "」", "と", "言っ", "た", "。"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_ipadic_analyzer_analyze_batch() {
+        let config_str = r#"
+        {
+            "character_filters": [
+                {
+                    "kind": "unicode_normalize",
+                    "args": {
+                        "kind": "nfkc"
+                    }
+                }
+            ],
+            "tokenizer": {
+                "dictionary": {
+                    "kind": "ipadic"
+                },
+                "mode": "normal"
+            },
+            "token_filters": [
+                {
+                    "kind": "japanese_stop_tags",
+                    "args": {
+                        "tags": [
+                            "助詞",
+                            "記号"
+                        ]
+                    }
+                }
+            ]
+        }
+        "#;
+        let analyzer = Analyzer::from_slice(config_str.as_bytes()).unwrap();
+
+        let texts = ["ｶﾞｿﾘﾝの値段", "東京へ行く。", ""];
+        let batch = analyzer.analyze_batch(&texts).unwrap();
+        assert_eq!(batch.len(), texts.len());
+        for (tokens, text) in batch.iter().zip(texts) {
+            let expected = analyzer.analyze(text).unwrap();
+            assert_eq!(
+                tokens
+                    .iter()
+                    .map(|t| (t.text.as_ref(), t.byte_start, t.byte_end))
+                    .collect::<Vec<_>>(),
+                expected
+                    .iter()
+                    .map(|t| (t.text.as_ref(), t.byte_start, t.byte_end))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
     #[test]
     #[cfg(feature = "ipadic")]
     fn test_analyzer_from_slice_wrong_config() {
@@ -34,6 +34,40 @@ pub fn build_dictionary(
     resolve_builder(dictionary_type)?.build_dictionary(input_dir, output_dir)
 }
 
+/// Builds the dictionary into a single-file bundle, e.g. `ipadic.lindera`.
+/// The dictionary files are built in a temporary directory next to the bundle first.
+///
+/// # Arguments
+///
+/// * `dictionary_type`: The kind of the dictionary.
+/// * `input_dir`: The directory of the dictionary source.
+/// * `output_file`: The bundle file to write.
+///
+/// returns: Result<(), LinderaError>
+///
+pub fn build_dictionary_bundle(
+    dictionary_type: DictionaryKind,
+    input_dir: &Path,
+    output_file: &Path,
+) -> LinderaResult<()> {
+    let parent_dir = match output_file.parent() {
+        Some(parent_dir) if !parent_dir.as_os_str().is_empty() => parent_dir,
+        _ => Path::new("."),
+    };
+    // A fresh directory, so that no existing directory is overwritten or removed.
+    let work_dir = tempfile::Builder::new()
+        .prefix(".lindera-build")
+        .tempdir_in(parent_dir)
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+
+    build_dictionary(dictionary_type, input_dir, work_dir.path())?;
+    lindera_dictionary::write_bundle(work_dir.path(), output_file)?;
+
+    work_dir
+        .close()
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+}
+
 pub fn build_user_dictionary(
     dictionary_type: DictionaryKind,
     input_file: &Path,
@@ -121,3 +155,59 @@ pub fn load_user_dictionary(
 
     Ok(user_dictionary)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use lindera_core::dictionary::Dictionary;
+
+    use crate::{
+        builder::{build_dictionary_bundle, load_dictionary},
+        tokenizer::{mini_unidic_dictionary_dir, DictionaryConfig, Tokenizer, TokenizerConfig},
+        DictionaryKind,
+    };
+
+    #[test]
+    fn test_build_dictionary_bundle() {
+        let input_dir = mini_unidic_dictionary_dir();
+        let output_dir = tempfile::tempdir().unwrap();
+        let output_file = output_dir.path().join("mini.lindera");
+
+        // A directory named like the old work directory is left alone.
+        let other_dir = output_dir.path().join("mini.lindera.build");
+        fs::create_dir(&other_dir).unwrap();
+        fs::write(other_dir.join("keep.txt"), "keep").unwrap();
+
+        build_dictionary_bundle(DictionaryKind::UniDic, &input_dir, &output_file).unwrap();
+        assert!(other_dir.join("keep.txt").exists());
+        // Only the bundle and the other directory remain.
+        assert_eq!(fs::read_dir(output_dir.path()).unwrap().count(), 2);
+
+        let dictionary = load_dictionary(DictionaryConfig {
+            kind: None,
+            path: Some(output_file.clone()),
+        })
+        .unwrap();
+        assert_eq!(dictionary.kind, Some(DictionaryKind::UniDic));
+        let dictionary = Dictionary::from_bytes(&fs::read(&output_file).unwrap()).unwrap();
+        assert_eq!(dictionary.kind, Some(DictionaryKind::UniDic));
+
+        let tokenizer = Tokenizer::with_config(TokenizerConfig {
+            dictionary: DictionaryConfig {
+                kind: None,
+                path: Some(output_file),
+            },
+            ..TokenizerConfig::default()
+        })
+        .unwrap();
+        let tokens = tokenizer
+            .tokenize_with_details("羽田空港限定トートバッグ")
+            .unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
+            vec!["羽田空港", "限定", "トートバッグ"]
+        );
+        assert_eq!(tokens[0].pos(), Some("名詞"));
+    }
+}
@@ -0,0 +1,101 @@
+pub mod no_split;
+pub mod rule_based;
+
+use serde::{Deserialize, Serialize};
+
+use lindera_core::sentence_splitter::SentenceSplitter;
+
+use crate::{
+    sentence_splitter::{
+        no_split::NoSplitSentenceSplitter,
+        rule_based::{RuleBasedSentenceSplitter, RuleBasedSentenceSplitterConfig},
+    },
+    DictionaryKind,
+};
+
+/// Sentence splitter config
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", content = "args")]
+pub enum SentenceSplitterConfig {
+    /// Tokenize the whole text as a single sentence.
+    #[serde(rename = "no_split")]
+    NoSplit,
+    /// Split sentences with punctuation rules.
+    #[serde(rename = "rule_based")]
+    RuleBased(RuleBasedSentenceSplitterConfig),
+}
+
+impl SentenceSplitterConfig {
+    pub fn into_sentence_splitter(self) -> Box<dyn SentenceSplitter> {
+        match self {
+            SentenceSplitterConfig::NoSplit => Box::new(NoSplitSentenceSplitter::new()),
+            SentenceSplitterConfig::RuleBased(config) => {
+                Box::new(RuleBasedSentenceSplitter::new(config))
+            }
+        }
+    }
+}
+
+/// Returns the default sentence splitter for the dictionary kind.
+/// An unknown kind gets the Japanese rules, which keep the `。` and `、` splits of the
+/// previous versions.
+pub fn default_sentence_splitter(kind: Option<&DictionaryKind>) -> Box<dyn SentenceSplitter> {
+    let config = match kind {
+        Some(DictionaryKind::IPADIC) | Some(DictionaryKind::UniDic) | None => {
+            RuleBasedSentenceSplitterConfig::japanese()
+        }
+        Some(DictionaryKind::KoDic) => RuleBasedSentenceSplitterConfig::korean(),
+        Some(DictionaryKind::CcCedict) => RuleBasedSentenceSplitterConfig::chinese(),
+    };
+
+    Box::new(RuleBasedSentenceSplitter::new(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        sentence_splitter::{
+            default_sentence_splitter, rule_based::RuleBasedSentenceSplitterConfig,
+            SentenceSplitterConfig,
+        },
+        DictionaryKind,
+    };
+
+    #[test]
+    fn test_sentence_splitter_config_from_json() {
+        let config_str = r#"
+        {
+            "kind": "rule_based",
+            "args": {
+                "terminators": ["。"],
+                "brackets": [["「", "」"]]
+            }
+        }
+        "#;
+        let config: SentenceSplitterConfig = serde_json::from_str(config_str).unwrap();
+        assert_eq!(
+            config,
+            SentenceSplitterConfig::RuleBased(RuleBasedSentenceSplitterConfig::new(
+                vec!['。'],
+                vec![],
+                vec![('「', '」')],
+                false
+            ))
+        );
+
+        let config: SentenceSplitterConfig =
+            serde_json::from_str(r#"{"kind": "no_split"}"#).unwrap();
+        assert_eq!(config, SentenceSplitterConfig::NoSplit);
+        assert_eq!(config.into_sentence_splitter().name(), "no_split");
+    }
+
+    #[test]
+    fn test_default_sentence_splitter() {
+        let sentence_splitter = default_sentence_splitter(None);
+        assert_eq!(sentence_splitter.name(), "rule_based");
+        assert_eq!(sentence_splitter.split("東京、大阪。"), vec![0..9, 9..18]);
+
+        let sentence_splitter = default_sentence_splitter(Some(&DictionaryKind::KoDic));
+        assert_eq!(sentence_splitter.split("東京、大阪。"), vec![0..18]);
+    }
+}
@@ -0,0 +1,44 @@
+use std::ops::Range;
+
+use lindera_core::sentence_splitter::SentenceSplitter;
+
+pub const NO_SPLIT_SENTENCE_SPLITTER_NAME: &str = "no_split";
+
+/// Treats the whole text as a single sentence.
+#[derive(Clone, Debug, Default)]
+pub struct NoSplitSentenceSplitter {}
+
+impl NoSplitSentenceSplitter {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl SentenceSplitter for NoSplitSentenceSplitter {
+    fn name(&self) -> &'static str {
+        NO_SPLIT_SENTENCE_SPLITTER_NAME
+    }
+
+    fn split(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        if !text.is_empty() {
+            ranges.push(0..text.len());
+        }
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lindera_core::sentence_splitter::SentenceSplitter;
+
+    use crate::sentence_splitter::no_split::NoSplitSentenceSplitter;
+
+    #[test]
+    fn test_no_split_sentence_splitter_split() {
+        let sentence_splitter = NoSplitSentenceSplitter::new();
+
+        assert_eq!(sentence_splitter.split("東京。大阪。"), vec![0..18]);
+        assert!(sentence_splitter.split("").is_empty());
+    }
+}
@@ -0,0 +1,344 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use lindera_core::sentence_splitter::SentenceSplitter;
+
+use crate::{error::LinderaErrorKind, LinderaResult};
+
+pub const RULE_BASED_SENTENCE_SPLITTER_NAME: &str = "rule_based";
+
+const DEFAULT_BRACKETS: &[(char, char)] = &[
+    ('「', '」'),
+    ('『', '』'),
+    ('（', '）'),
+    ('(', ')'),
+    ('［', '］'),
+    ('[', ']'),
+    ('【', '】'),
+    ('〈', '〉'),
+    ('《', '》'),
+    ('〔', '〕'),
+    ('“', '”'),
+    ('‘', '’'),
+];
+
+/// Brackets are not expected to stay open for longer than this number of characters.
+pub const DEFAULT_MAX_BRACKET_LEN: usize = 200;
+
+fn default_max_bracket_len() -> usize {
+    DEFAULT_MAX_BRACKET_LEN
+}
+
+/// Rule based sentence splitter config
+///
+/// - terminators: Characters that end a sentence.
+/// - spaced_terminators: Characters that end a sentence only when followed by whitespace or the end of the text (e.g. `.` in "3.14" does not split).
+/// - brackets: Pairs of opening and closing brackets. Terminators inside brackets do not split.
+/// - split_on_newline: Whether a newline always ends a sentence.
+/// - max_bracket_len: The number of characters after which a bracket that is still open is regarded as unbalanced and no longer prevents splitting.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct RuleBasedSentenceSplitterConfig {
+    #[serde(default)]
+    pub terminators: Vec<char>,
+    #[serde(default)]
+    pub spaced_terminators: Vec<char>,
+    #[serde(default)]
+    pub brackets: Vec<(char, char)>,
+    #[serde(default)]
+    pub split_on_newline: bool,
+    #[serde(default = "default_max_bracket_len")]
+    pub max_bracket_len: usize,
+}
+
+impl RuleBasedSentenceSplitterConfig {
+    pub fn new(
+        terminators: Vec<char>,
+        spaced_terminators: Vec<char>,
+        brackets: Vec<(char, char)>,
+        split_on_newline: bool,
+    ) -> Self {
+        Self {
+            terminators,
+            spaced_terminators,
+            brackets,
+            split_on_newline,
+            max_bracket_len: DEFAULT_MAX_BRACKET_LEN,
+        }
+    }
+
+    pub fn from_slice(data: &[u8]) -> LinderaResult<Self> {
+        serde_json::from_slice(data).map_err(|err| LinderaErrorKind::Deserialize.with_error(err))
+    }
+
+    /// Japanese punctuation. `、` is kept as a terminator for compatibility with the previous behaviour.
+    pub fn japanese() -> Self {
+        Self::new(
+            vec!['。', '、', '！', '？', '!', '?'],
+            vec!['.'],
+            DEFAULT_BRACKETS.to_vec(),
+            true,
+        )
+    }
+
+    pub fn korean() -> Self {
+        Self::new(
+            vec!['。', '！', '？'],
+            vec!['.', '!', '?'],
+            DEFAULT_BRACKETS.to_vec(),
+            true,
+        )
+    }
+
+    pub fn chinese() -> Self {
+        Self::new(
+            vec!['。', '！', '？', '；'],
+            vec!['.', '!', '?'],
+            DEFAULT_BRACKETS.to_vec(),
+            true,
+        )
+    }
+}
+
+impl Default for RuleBasedSentenceSplitterConfig {
+    fn default() -> Self {
+        Self::new(
+            vec!['。', '！', '？'],
+            vec!['.', '!', '?'],
+            DEFAULT_BRACKETS.to_vec(),
+            true,
+        )
+    }
+}
+
+/// Splits sentences at terminators that are not enclosed in brackets.
+#[derive(Clone, Debug)]
+pub struct RuleBasedSentenceSplitter {
+    config: RuleBasedSentenceSplitterConfig,
+}
+
+impl RuleBasedSentenceSplitter {
+    pub fn new(config: RuleBasedSentenceSplitterConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn from_slice(data: &[u8]) -> LinderaResult<Self> {
+        Ok(Self::new(RuleBasedSentenceSplitterConfig::from_slice(
+            data,
+        )?))
+    }
+
+    fn is_terminator(&self, c: char) -> bool {
+        self.config.terminators.contains(&c) || self.config.spaced_terminators.contains(&c)
+    }
+
+    fn is_closer(&self, c: char) -> bool {
+        self.config.brackets.iter().any(|(_, close)| *close == c)
+    }
+
+    fn closer_of(&self, c: char) -> Option<char> {
+        self.config
+            .brackets
+            .iter()
+            .find(|(open, _)| *open == c)
+            .map(|(_, close)| *close)
+    }
+
+    /// Updates the bracket stack with the character at the character position `pos`.
+    /// The stack holds the expected closers and the positions of their openers.
+    fn track_bracket(&self, c: char, pos: usize, stack: &mut Vec<(char, usize)>) {
+        // Brackets open for too long are most likely never closed, e.g. a stray "「".
+        // Forgetting them keeps one typo from joining the rest of the text into a single sentence.
+        let expired = stack
+            .iter()
+            .take_while(|(_, open)| pos - open > self.config.max_bracket_len)
+            .count();
+        stack.drain(..expired);
+
+        // Symmetric quotes such as '"' close the innermost bracket first.
+        if matches!(stack.last(), Some((close, _)) if *close == c) {
+            stack.pop();
+        } else if let Some(close) = self.closer_of(c) {
+            stack.push((close, pos));
+        } else if self.is_closer(c) {
+            // Unbalanced closers are ignored; otherwise unwind to the matching opener.
+            if let Some(index) = stack.iter().rposition(|(close, _)| *close == c) {
+                stack.truncate(index);
+            }
+        }
+    }
+}
+
+impl SentenceSplitter for RuleBasedSentenceSplitter {
+    fn name(&self) -> &'static str {
+        RULE_BASED_SENTENCE_SPLITTER_NAME
+    }
+
+    fn split(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        let mut stack: Vec<(char, usize)> = Vec::new();
+        let mut start = 0;
+
+        let mut chars = text.char_indices().enumerate().peekable();
+        while let Some((pos, (i, c))) = chars.next() {
+            if c == '\n' && self.config.split_on_newline {
+                let end = i + c.len_utf8();
+                ranges.push(start..end);
+                start = end;
+                stack.clear();
+                continue;
+            }
+
+            self.track_bracket(c, pos, &mut stack);
+            if !self.is_terminator(c) {
+                continue;
+            }
+
+            // Terminators inside brackets never end a sentence, e.g. "「行く。」と言った。".
+            let inside_brackets = !stack.is_empty();
+
+            // Consume successive terminators and closing brackets, e.g. "！？" or "。」".
+            let spaced = !self.config.terminators.contains(&c);
+            let mut end = i + c.len_utf8();
+            while let Some(&(next_pos, (j, next))) = chars.peek() {
+                if self.is_terminator(next) || (self.is_closer(next) && !stack.is_empty()) {
+                    self.track_bracket(next, next_pos, &mut stack);
+                    end = j + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            if inside_brackets || !stack.is_empty() {
+                continue;
+            }
+            if spaced {
+                match chars.peek() {
+                    Some((_, (_, next))) if !next.is_whitespace() => continue,
+                    _ => {}
+                }
+            }
+
+            ranges.push(start..end);
+            start = end;
+        }
+
+        if start < text.len() {
+            ranges.push(start..text.len());
+        }
+
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lindera_core::sentence_splitter::SentenceSplitter;
+
+    use crate::sentence_splitter::rule_based::{
+        RuleBasedSentenceSplitter, RuleBasedSentenceSplitterConfig, DEFAULT_MAX_BRACKET_LEN,
+    };
+
+    fn split<'a>(sentence_splitter: &RuleBasedSentenceSplitter, text: &'a str) -> Vec<&'a str> {
+        sentence_splitter
+            .split(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_rule_based_sentence_splitter_config_from_slice() {
+        let config_str = r#"
+        {
+            "terminators": ["。"],
+            "brackets": [["「", "」"]]
+        }
+        "#;
+        let config = RuleBasedSentenceSplitterConfig::from_slice(config_str.as_bytes()).unwrap();
+        assert_eq!(config.terminators, vec!['。']);
+        assert!(config.spaced_terminators.is_empty());
+        assert_eq!(config.brackets, vec![('「', '」')]);
+        assert!(!config.split_on_newline);
+        assert_eq!(config.max_bracket_len, DEFAULT_MAX_BRACKET_LEN);
+    }
+
+    #[test]
+    fn test_rule_based_sentence_splitter_split_japanese() {
+        let sentence_splitter =
+            RuleBasedSentenceSplitter::new(RuleBasedSentenceSplitterConfig::japanese());
+
+        assert_eq!(
+            split(&sentence_splitter, "東京へ行く。大阪へ行く"),
+            vec!["東京へ行く。", "大阪へ行く"]
+        );
+        assert_eq!(
+            split(&sentence_splitter, "本当！？そうだ。"),
+            vec!["本当！？", "そうだ。"]
+        );
+        assert_eq!(
+            split(&sentence_splitter, "「行く。」と言った。"),
+            vec!["「行く。」と言った。"]
+        );
+        assert!(split(&sentence_splitter, "").is_empty());
+    }
+
+    #[test]
+    fn test_rule_based_sentence_splitter_split_nested_brackets() {
+        let sentence_splitter =
+            RuleBasedSentenceSplitter::new(RuleBasedSentenceSplitterConfig::japanese());
+
+        assert_eq!(
+            split(
+                &sentence_splitter,
+                "彼は「『走れ。』と（誰かが。）叫んだ。」と書いた。次。"
+            ),
+            vec!["彼は「『走れ。』と（誰かが。）叫んだ。」と書いた。", "次。"]
+        );
+        assert_eq!(
+            split(&sentence_splitter, "終わり。」次。"),
+            vec!["終わり。", "」次。"]
+        );
+    }
+
+    #[test]
+    fn test_rule_based_sentence_splitter_split_unbalanced_brackets() {
+        let mut config = RuleBasedSentenceSplitterConfig::japanese();
+        config.max_bracket_len = 10;
+        let sentence_splitter = RuleBasedSentenceSplitter::new(config);
+
+        // The opener stops preventing splits 10 characters after it.
+        assert_eq!(
+            split(&sentence_splitter, "「東京へ行く。大阪へ行く。京都へ行く。"),
+            vec!["「東京へ行く。大阪へ行く。", "京都へ行く。"]
+        );
+        assert_eq!(
+            split(&sentence_splitter, "「行く。」と言った。次。"),
+            vec!["「行く。」と言った。", "次。"]
+        );
+
+        let sentence_splitter =
+            RuleBasedSentenceSplitter::new(RuleBasedSentenceSplitterConfig::japanese());
+        let text = format!("（{}。次。", "あ".repeat(300));
+        assert_eq!(
+            split(&sentence_splitter, &text),
+            vec![&text[..text.len() - "次。".len()], "次。"]
+        );
+    }
+
+    #[test]
+    fn test_rule_based_sentence_splitter_split_spaced_terminators() {
+        let sentence_splitter =
+            RuleBasedSentenceSplitter::new(RuleBasedSentenceSplitterConfig::default());
+
+        assert_eq!(
+            split(&sentence_splitter, "Pi is 3.14. It is (not. really) known!"),
+            vec!["Pi is 3.14.", " It is (not. really) known!"]
+        );
+        assert_eq!(
+            split(&sentence_splitter, "line one\nline two"),
+            vec!["line one\n", "line two"]
+        );
+    }
+}
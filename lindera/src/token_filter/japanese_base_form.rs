@@ -3,6 +3,10 @@ use std::borrow::Cow;
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "ipadic")]
+use lindera_core::token_features::IpadicFeatures;
+#[cfg(feature = "unidic")]
+use lindera_core::token_features::UnidicFeatures;
 use lindera_core::token_filter::TokenFilter;
 
 use crate::{error::LinderaErrorKind, DictionaryKind, LinderaResult, Token};
@@ -49,24 +53,32 @@ impl TokenFilter for JapaneseBaseFormTokenFilter {
 
     fn apply<'a>(&self, tokens: &mut Vec<Token<'a>>) -> LinderaResult<()> {
         for token in tokens.iter_mut() {
-            if let Some(details) = &token.details {
-                if &details[0] == "UNK" {
-                    // NOOP
-                    continue;
-                }
-                match self.config.kind {
-                    #[cfg(feature = "ipadic")]
-                    DictionaryKind::IPADIC => {
-                        token.text = Cow::Owned(details[6].clone());
-                    }
-                    #[cfg(feature = "unidic")]
-                    DictionaryKind::UniDic => {
-                        token.text = Cow::Owned(details[10].clone());
+            if token.is_unknown() {
+                // Unknown words have no base form.
+                continue;
+            }
+            match self.config.kind {
+                #[cfg(feature = "ipadic")]
+                DictionaryKind::IPADIC => {
+                    if let Some(base_form) = token
+                        .details
+                        .as_deref()
+                        .and_then(|details| IpadicFeatures::from_details(details).base_form)
+                    {
+                        token.text = Cow::Owned(base_form.to_string());
                     }
-                    _ => {
-                        // NOOP
+                }
+                #[cfg(feature = "unidic")]
+                DictionaryKind::UniDic => {
+                    if let Some(base_form) = token.details.as_deref().and_then(|details| {
+                        UnidicFeatures::from_details(details).orthography_base_form
+                    }) {
+                        token.text = Cow::Owned(base_form.to_string());
                     }
                 }
+                _ => {
+                    // NOOP
+                }
             }
         }
 
@@ -168,6 +180,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("に"),
@@ -184,6 +197,7 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 15,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("あり"),
@@ -200,6 +214,7 @@ mod tests {
                 ]),
                 byte_start: 15,
                 byte_end: 21,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("ます"),
@@ -216,6 +231,7 @@ mod tests {
                 ]),
                 byte_start: 21,
                 byte_end: 27,
+                ..Default::default()
             },
         ];
 
@@ -262,6 +278,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 6,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("空港"),
@@ -286,6 +303,7 @@ mod tests {
                 ]),
                 byte_start: 6,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("に"),
@@ -310,6 +328,7 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 15,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("あり"),
@@ -334,6 +353,7 @@ mod tests {
                 ]),
                 byte_start: 15,
                 byte_end: 21,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("ます"),
@@ -358,6 +378,7 @@ mod tests {
                 ]),
                 byte_start: 21,
                 byte_end: 27,
+                ..Default::default()
             },
         ];
 
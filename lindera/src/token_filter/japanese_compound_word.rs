@@ -118,6 +118,16 @@ impl TokenFilter for JapaneseCompoundWordTokenFilter {
                             details: token.details.clone(),
                             byte_start: token.byte_start,
                             byte_end: token.byte_end,
+                            confidence: token.confidence,
+                            dictionary_kind: token.dictionary_kind.clone(),
+                            schema: token.schema.clone(),
+                            metadata: token.metadata,
+                            char_start: token.char_start,
+                            char_end: token.char_end,
+                            utf16_start: token.utf16_start,
+                            utf16_end: token.utf16_end,
+                            position: token.position,
+                            position_length: token.position_length,
                         });
                     } else {
                         let compound_token = compound_token_opt.take().unwrap();
@@ -128,6 +138,30 @@ impl TokenFilter for JapaneseCompoundWordTokenFilter {
                             ),
                             byte_start: compound_token.byte_start,
                             byte_end: token.byte_end,
+                            // A compound word is only as reliable as its least reliable part.
+                            confidence: match (compound_token.confidence, token.confidence) {
+                                (Some(a), Some(b)) => Some(a.min(b)),
+                                _ => None,
+                            },
+                            dictionary_kind: compound_token.dictionary_kind,
+                            schema: compound_token.schema,
+                            metadata: None,
+                            char_start: compound_token.char_start,
+                            char_end: token.char_end,
+                            utf16_start: compound_token.utf16_start,
+                            utf16_end: token.utf16_end,
+                            // The compound word spans the positions of all its parts.
+                            position: compound_token.position,
+                            position_length: match (
+                                compound_token.position,
+                                token.position,
+                                token.position_length,
+                            ) {
+                                (Some(start), Some(position), Some(length)) => {
+                                    Some(position + length - start)
+                                }
+                                _ => None,
+                            },
                         });
                     }
                 } else {
@@ -140,6 +174,16 @@ impl TokenFilter for JapaneseCompoundWordTokenFilter {
                         details: token.details.clone(),
                         byte_start: token.byte_start,
                         byte_end: token.byte_end,
+                        confidence: token.confidence,
+                        dictionary_kind: token.dictionary_kind.clone(),
+                        schema: token.schema.clone(),
+                        metadata: token.metadata,
+                        char_start: token.char_start,
+                        char_end: token.char_end,
+                        utf16_start: token.utf16_start,
+                        utf16_end: token.utf16_end,
+                        position: token.position,
+                        position_length: token.position_length,
                     });
                 }
             }
@@ -229,6 +273,9 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 3,
+                position: Some(0),
+                position_length: Some(1),
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("０"),
@@ -245,6 +292,9 @@ mod tests {
                 ]),
                 byte_start: 3,
                 byte_end: 6,
+                position: Some(1),
+                position_length: Some(1),
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("０"),
@@ -261,6 +311,9 @@ mod tests {
                 ]),
                 byte_start: 6,
                 byte_end: 9,
+                position: Some(2),
+                position_length: Some(1),
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("円"),
@@ -277,6 +330,9 @@ mod tests {
                 ]),
                 byte_start: 9,
                 byte_end: 12,
+                position: Some(3),
+                position_length: Some(1),
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("玉"),
@@ -293,6 +349,9 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 15,
+                position: Some(4),
+                position_length: Some(1),
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("を"),
@@ -309,6 +368,9 @@ mod tests {
                 ]),
                 byte_start: 27,
                 byte_end: 30,
+                position: Some(5),
+                position_length: Some(1),
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("拾う"),
@@ -325,6 +387,9 @@ mod tests {
                 ]),
                 byte_start: 30,
                 byte_end: 36,
+                position: Some(6),
+                position_length: Some(1),
+                ..Default::default()
             },
         ];
 
@@ -335,6 +400,18 @@ mod tests {
         assert_eq!(tokens[1].text, "玉");
         assert_eq!(tokens[2].text, "を");
         assert_eq!(tokens[3].text, "拾う");
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|t| (t.position, t.position_length))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(0), Some(4)),
+                (Some(4), Some(1)),
+                (Some(5), Some(1)),
+                (Some(6), Some(1))
+            ]
+        );
 
         assert_eq!(
             tokens[0].details,
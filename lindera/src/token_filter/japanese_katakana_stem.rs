@@ -163,24 +163,28 @@ mod tests {
                 details: None,
                 byte_start: 0,
                 byte_end: 6,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("レバー"),
                 details: None,
                 byte_start: 7,
                 byte_end: 16,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("サッカー"),
                 details: None,
                 byte_start: 17,
                 byte_end: 29,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("レシーバー"),
                 details: None,
                 byte_start: 30,
                 byte_end: 45,
+                ..Default::default()
             },
         ];
 
@@ -257,6 +257,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 9,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("も"),
@@ -273,6 +274,7 @@ mod tests {
                 ]),
                 byte_start: 9,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("もも"),
@@ -289,6 +291,7 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 18,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("も"),
@@ -305,6 +308,7 @@ mod tests {
                 ]),
                 byte_start: 18,
                 byte_end: 21,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("もも"),
@@ -321,6 +325,7 @@ mod tests {
                 ]),
                 byte_start: 21,
                 byte_end: 27,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("の"),
@@ -337,6 +342,7 @@ mod tests {
                 ]),
                 byte_start: 27,
                 byte_end: 30,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("うち"),
@@ -353,6 +359,7 @@ mod tests {
                 ]),
                 byte_start: 30,
                 byte_end: 36,
+                ..Default::default()
             },
         ];
 
@@ -865,6 +865,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 3,
+                ..Default::default()
             }];
 
             filter.apply(&mut tokens).unwrap();
@@ -889,6 +890,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 9,
+                ..Default::default()
             }];
 
             filter.apply(&mut tokens).unwrap();
@@ -913,6 +915,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 129,
+                ..Default::default()
             }];
 
             filter.apply(&mut tokens).unwrap();
@@ -956,6 +959,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 3,
+                ..Default::default()
             }];
 
             filter.apply(&mut tokens).unwrap();
@@ -988,6 +992,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 9,
+                ..Default::default()
             }];
 
             filter.apply(&mut tokens).unwrap();
@@ -1020,6 +1025,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 129,
+                ..Default::default()
             }];
 
             filter.apply(&mut tokens).unwrap();
@@ -3,6 +3,10 @@ use std::borrow::Cow;
 
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "ipadic")]
+use lindera_core::token_features::IpadicFeatures;
+#[cfg(feature = "unidic")]
+use lindera_core::token_features::UnidicFeatures;
 use lindera_core::token_filter::TokenFilter;
 
 use crate::{error::LinderaErrorKind, DictionaryKind, LinderaResult, Token};
@@ -49,24 +53,34 @@ impl TokenFilter for JapaneseReadingFormTokenFilter {
 
     fn apply<'a>(&self, tokens: &mut Vec<Token<'a>>) -> LinderaResult<()> {
         for token in tokens.iter_mut() {
-            if let Some(details) = &token.details {
-                if &details[0] == "UNK" {
-                    // NOOP
-                    continue;
-                }
-                match self.config.kind {
-                    #[cfg(feature = "ipadic")]
-                    DictionaryKind::IPADIC => {
-                        token.text = Cow::Owned(details[7].clone());
-                    }
-                    #[cfg(feature = "unidic")]
-                    DictionaryKind::UniDic => {
-                        token.text = Cow::Owned(details[6].clone());
+            if token.is_unknown() {
+                // Unknown words have no reading.
+                continue;
+            }
+            match self.config.kind {
+                #[cfg(feature = "ipadic")]
+                DictionaryKind::IPADIC => {
+                    if let Some(reading) = token
+                        .details
+                        .as_deref()
+                        .and_then(|details| IpadicFeatures::from_details(details).reading)
+                    {
+                        token.text = Cow::Owned(reading.to_string());
                     }
-                    _ => {
-                        // NOOP
+                }
+                #[cfg(feature = "unidic")]
+                DictionaryKind::UniDic => {
+                    if let Some(reading) = token
+                        .details
+                        .as_deref()
+                        .and_then(|details| UnidicFeatures::from_details(details).lexeme_reading)
+                    {
+                        token.text = Cow::Owned(reading.to_string());
                     }
                 }
+                _ => {
+                    // NOOP
+                }
             }
         }
 
@@ -80,7 +94,9 @@ mod tests {
     use std::borrow::Cow;
 
     #[cfg(any(feature = "ipadic", feature = "unidic",))]
-    use lindera_core::token_filter::TokenFilter;
+    use lindera_core::{
+        token::TokenMetadata, token_filter::TokenFilter, viterbi::EdgeType, word_entry::WordId,
+    };
 
     #[cfg(any(feature = "ipadic", feature = "unidic",))]
     use crate::{
@@ -90,6 +106,18 @@ mod tests {
         DictionaryKind, Token,
     };
 
+    #[cfg(any(feature = "ipadic", feature = "unidic",))]
+    fn unknown_metadata() -> Option<TokenMetadata> {
+        Some(TokenMetadata {
+            word_id: WordId::unknown(0),
+            edge_type: EdgeType::UNKNOWN,
+            word_cost: 0,
+            left_context_id: 0,
+            right_context_id: 0,
+            path_cost: 0,
+        })
+    }
+
     #[cfg(feature = "ipadic")]
     #[test]
     fn test_japanese_reading_form_token_filter_config_from_slice_ipadic() {
@@ -170,6 +198,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("限定"),
@@ -186,21 +215,53 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 18,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("トートバッグ"),
-                details: Some(vec!["UNK".to_string()]),
+                details: Some(vec![
+                    "名詞".to_string(),
+                    "一般".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                ]),
                 byte_start: 18,
                 byte_end: 36,
+                ..Default::default()
+            },
+            // Unknown words keep their text even if their details look like a known word's.
+            Token {
+                text: Cow::Borrowed("鞄"),
+                details: Some(vec![
+                    "名詞".to_string(),
+                    "一般".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "鞄".to_string(),
+                    "カバン".to_string(),
+                    "カバン".to_string(),
+                ]),
+                byte_start: 36,
+                byte_end: 39,
+                metadata: unknown_metadata(),
+                ..Default::default()
             },
         ];
 
         filter.apply(&mut tokens).unwrap();
 
-        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens.len(), 4);
         assert_eq!(tokens[0].text, "ハネダクウコウ");
         assert_eq!(tokens[1].text, "ゲンテイ");
         assert_eq!(tokens[2].text, "トートバッグ");
+        assert_eq!(tokens[3].text, "鞄");
     }
 
     #[cfg(feature = "unidic")]
@@ -237,6 +298,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 6,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("空港"),
@@ -261,6 +323,7 @@ mod tests {
                 ]),
                 byte_start: 6,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("限定"),
@@ -285,6 +348,7 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 18,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("トート"),
@@ -309,6 +373,7 @@ mod tests {
                 ]),
                 byte_start: 18,
                 byte_end: 27,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("バッグ"),
@@ -333,16 +398,45 @@ mod tests {
                 ]),
                 byte_start: 27,
                 byte_end: 36,
+                ..Default::default()
+            },
+            // Unknown words keep their text even if their details look like a known word's.
+            Token {
+                text: Cow::Borrowed("鞄"),
+                details: Some(vec![
+                    "名詞".to_string(),
+                    "普通名詞".to_string(),
+                    "一般".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "カバン".to_string(),
+                    "鞄".to_string(),
+                    "鞄".to_string(),
+                    "カバン".to_string(),
+                    "鞄".to_string(),
+                    "カバン".to_string(),
+                    "和".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                    "*".to_string(),
+                ]),
+                byte_start: 36,
+                byte_end: 39,
+                metadata: unknown_metadata(),
+                ..Default::default()
             },
         ];
 
         filter.apply(&mut tokens).unwrap();
 
-        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens.len(), 6);
         assert_eq!(tokens[0].text, "ハタ");
         assert_eq!(tokens[1].text, "クウコウ");
         assert_eq!(tokens[2].text, "ゲンテイ");
         assert_eq!(tokens[3].text, "トート");
         assert_eq!(tokens[4].text, "バッグ");
+        assert_eq!(tokens[5].text, "鞄");
     }
 }
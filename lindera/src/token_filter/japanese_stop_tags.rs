@@ -220,6 +220,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 9,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("も"),
@@ -236,6 +237,7 @@ mod tests {
                 ]),
                 byte_start: 9,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("もも"),
@@ -252,6 +254,7 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 18,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("も"),
@@ -268,6 +271,7 @@ mod tests {
                 ]),
                 byte_start: 18,
                 byte_end: 21,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("もも"),
@@ -284,6 +288,7 @@ mod tests {
                 ]),
                 byte_start: 21,
                 byte_end: 27,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("の"),
@@ -300,6 +305,7 @@ mod tests {
                 ]),
                 byte_start: 27,
                 byte_end: 30,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("うち"),
@@ -316,6 +322,7 @@ mod tests {
                 ]),
                 byte_start: 30,
                 byte_end: 36,
+                ..Default::default()
             },
         ];
 
@@ -108,78 +108,91 @@ mod tests {
                 details: None,
                 byte_start: 0,
                 byte_end: 4,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("製"),
                 details: None,
                 byte_start: 4,
                 byte_end: 7,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("形態素"),
                 details: None,
                 byte_start: 7,
                 byte_end: 16,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("解析"),
                 details: None,
                 byte_start: 16,
                 byte_end: 22,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("器"),
                 details: None,
                 byte_start: 22,
                 byte_end: 25,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("Lindera"),
                 details: None,
                 byte_start: 25,
                 byte_end: 32,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("で"),
                 details: None,
                 byte_start: 32,
                 byte_end: 35,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("日本語"),
                 details: None,
                 byte_start: 35,
                 byte_end: 44,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("を"),
                 details: None,
                 byte_start: 44,
                 byte_end: 47,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("形態素"),
                 details: None,
                 byte_start: 47,
                 byte_end: 56,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("解析"),
                 details: None,
                 byte_start: 56,
                 byte_end: 62,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("する"),
                 details: None,
                 byte_start: 62,
                 byte_end: 68,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("。"),
                 details: None,
                 byte_start: 68,
                 byte_end: 71,
+                ..Default::default()
             },
         ];
 
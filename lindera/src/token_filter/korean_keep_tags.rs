@@ -126,6 +126,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 9,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("의"),
@@ -141,6 +142,7 @@ mod tests {
                 ]),
                 byte_start: 9,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("형태"),
@@ -156,6 +158,7 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 18,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("해석"),
@@ -171,6 +174,7 @@ mod tests {
                 ]),
                 byte_start: 18,
                 byte_end: 24,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("을"),
@@ -186,6 +190,7 @@ mod tests {
                 ]),
                 byte_start: 24,
                 byte_end: 27,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("실시"),
@@ -201,6 +206,7 @@ mod tests {
                 ]),
                 byte_start: 27,
                 byte_end: 33,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("할"),
@@ -216,6 +222,7 @@ mod tests {
                 ]),
                 byte_start: 33,
                 byte_end: 36,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("수"),
@@ -231,6 +238,7 @@ mod tests {
                 ]),
                 byte_start: 36,
                 byte_end: 39,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("있"),
@@ -246,6 +254,7 @@ mod tests {
                 ]),
                 byte_start: 39,
                 byte_end: 42,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("습니다"),
@@ -261,6 +270,7 @@ mod tests {
                 ]),
                 byte_start: 42,
                 byte_end: 51,
+                ..Default::default()
             },
         ];
 
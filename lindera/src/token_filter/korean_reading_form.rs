@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use lindera_core::token_filter::TokenFilter;
+use lindera_core::{token_features::KoDicFeatures, token_filter::TokenFilter};
 
 use crate::{LinderaResult, Token};
 
@@ -28,12 +28,14 @@ impl TokenFilter for KoreanReadingFormTokenFilter {
 
     fn apply<'a>(&self, tokens: &mut Vec<Token<'a>>) -> LinderaResult<()> {
         for token in tokens.iter_mut() {
+            if token.is_unknown() {
+                // Unknown words have no reading.
+                continue;
+            }
             if let Some(details) = &token.details {
-                if &details[0] == "UNK" {
-                    // NOOP
-                    continue;
+                if let Some(reading) = KoDicFeatures::from_details(details).reading {
+                    token.text = Cow::Owned(reading.to_string());
                 }
-                token.text = Cow::Owned(details[3].clone());
             }
         }
 
@@ -68,6 +70,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 9,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("의"),
@@ -83,6 +86,7 @@ mod tests {
                 ]),
                 byte_start: 9,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("형태"),
@@ -98,6 +102,7 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 18,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("해석"),
@@ -113,6 +118,7 @@ mod tests {
                 ]),
                 byte_start: 18,
                 byte_end: 24,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("을"),
@@ -128,6 +134,7 @@ mod tests {
                 ]),
                 byte_start: 24,
                 byte_end: 27,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("실시"),
@@ -143,6 +150,7 @@ mod tests {
                 ]),
                 byte_start: 27,
                 byte_end: 33,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("할"),
@@ -158,6 +166,7 @@ mod tests {
                 ]),
                 byte_start: 33,
                 byte_end: 36,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("수"),
@@ -173,6 +182,7 @@ mod tests {
                 ]),
                 byte_start: 36,
                 byte_end: 39,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("있"),
@@ -188,6 +198,7 @@ mod tests {
                 ]),
                 byte_start: 39,
                 byte_end: 42,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("습니다"),
@@ -203,6 +214,7 @@ mod tests {
                 ]),
                 byte_start: 42,
                 byte_end: 51,
+                ..Default::default()
             },
         ];
 
@@ -213,6 +213,7 @@ mod tests {
                 ]),
                 byte_start: 0,
                 byte_end: 9,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("의"),
@@ -228,6 +229,7 @@ mod tests {
                 ]),
                 byte_start: 9,
                 byte_end: 12,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("형태"),
@@ -243,6 +245,7 @@ mod tests {
                 ]),
                 byte_start: 12,
                 byte_end: 18,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("해석"),
@@ -258,6 +261,7 @@ mod tests {
                 ]),
                 byte_start: 18,
                 byte_end: 24,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("을"),
@@ -273,6 +277,7 @@ mod tests {
                 ]),
                 byte_start: 24,
                 byte_end: 27,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("실시"),
@@ -288,6 +293,7 @@ mod tests {
                 ]),
                 byte_start: 27,
                 byte_end: 33,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("할"),
@@ -303,6 +309,7 @@ mod tests {
                 ]),
                 byte_start: 33,
                 byte_end: 36,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("수"),
@@ -318,6 +325,7 @@ mod tests {
                 ]),
                 byte_start: 36,
                 byte_end: 39,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("있"),
@@ -333,6 +341,7 @@ mod tests {
                 ]),
                 byte_start: 39,
                 byte_end: 42,
+                ..Default::default()
             },
             Token {
                 text: Cow::Borrowed("습니다"),
@@ -348,6 +357,7 @@ mod tests {
                 ]),
                 byte_start: 42,
                 byte_end: 51,
+                ..Default::default()
             },
         ];
 
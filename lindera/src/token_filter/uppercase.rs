@@ -52,6 +52,7 @@ mod tests {
             details: None,
             byte_start: 0,
             byte_end: 4,
+            ..Default::default()
         }];
 
         filter.apply(&mut tokens).unwrap();
@@ -1,15 +1,24 @@
 use std::borrow::Cow;
 use std::fmt;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use byteorder::{ByteOrder, LittleEndian};
 use serde::{
     de::{self, MapAccess, SeqAccess, Visitor},
     Deserialize, Deserializer, Serialize,
 };
+use unicode_segmentation::UnicodeSegmentation;
 
 use lindera_core::{
-    dictionary::Dictionary, token::Token, user_dictionary::UserDictionary, viterbi::Lattice,
+    dictionary::Dictionary,
+    dictionary_schema::DictionarySchema,
+    sentence_splitter::SentenceSplitter,
+    token::{set_char_offsets, Token, TokenMetadata},
+    user_dictionary::UserDictionary,
+    viterbi::{
+        ConstrainedSpan, Constraints, EdgeId, EdgeType, Lattice, LatticeDump, WordCostOverrides,
+    },
     word_entry::WordId,
 };
 
@@ -17,12 +26,14 @@ use crate::{
     builder::{load_dictionary, load_user_dictionary},
     error::LinderaErrorKind,
     mode::Mode,
+    sentence_splitter::{default_sentence_splitter, SentenceSplitterConfig},
     DictionaryKind, LinderaResult,
 };
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub struct DictionaryConfig {
     pub kind: Option<DictionaryKind>,
+    /// The directory of the dictionary files, or a single-file dictionary bundle.
     pub path: Option<PathBuf>,
 }
 
@@ -45,7 +56,7 @@ pub const CONTAINED_DICTIONARIES: &[&str] = &[
 ];
 
 /// Tokenizer config
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq)]
 pub struct TokenizerConfig {
     /// The dictionary metadata
     pub dictionary: DictionaryConfig,
@@ -55,6 +66,17 @@ pub struct TokenizerConfig {
 
     /// Tokenize mode
     pub mode: Mode,
+
+    /// The temperature of the token confidence scores.
+    /// If set, each token carries the marginal probability of its edge in the lattice.
+    pub confidence_temperature: Option<f64>,
+
+    /// The sentence splitter.
+    /// If not set, the default splitter for the dictionary kind is used.
+    pub sentence_splitter: Option<SentenceSplitterConfig>,
+
+    /// The word costs of the dictionary entries to override at runtime.
+    pub word_cost_overrides: Vec<WordCostOverride>,
 }
 
 impl Default for TokenizerConfig {
@@ -68,6 +90,9 @@ impl Default for TokenizerConfig {
             },
             user_dictionary: None,
             mode: Mode::Normal,
+            confidence_temperature: None,
+            sentence_splitter: None,
+            word_cost_overrides: Vec::new(),
         }
     }
 }
@@ -81,6 +106,9 @@ impl<'de> Deserialize<'de> for TokenizerConfig {
             Dictionary,
             UserDictionary,
             Mode,
+            ConfidenceTemperature,
+            SentenceSplitter,
+            WordCostOverrides,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -94,7 +122,9 @@ impl<'de> Deserialize<'de> for TokenizerConfig {
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("`dictionary`, `user_dictionary` or `mode`")
+                        formatter.write_str(
+                            "`dictionary`, `user_dictionary`, `mode`, `confidence_temperature`, `sentence_splitter` or `word_cost_overrides`",
+                        )
                     }
 
                     fn visit_str<E>(self, value: &str) -> Result<Field, E>
@@ -105,6 +135,9 @@ impl<'de> Deserialize<'de> for TokenizerConfig {
                             "dictionary" => Ok(Field::Dictionary),
                             "user_dictionary" => Ok(Field::UserDictionary),
                             "mode" => Ok(Field::Mode),
+                            "confidence_temperature" => Ok(Field::ConfidenceTemperature),
+                            "sentence_splitter" => Ok(Field::SentenceSplitter),
+                            "word_cost_overrides" => Ok(Field::WordCostOverrides),
                             _ => Err(de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -132,11 +165,17 @@ impl<'de> Deserialize<'de> for TokenizerConfig {
                     .ok_or_else(|| de::Error::invalid_length(0, &self))?;
                 let user_dictionary = seq.next_element()?.unwrap_or(None);
                 let mode = seq.next_element()?.unwrap_or(Mode::Normal);
+                let confidence_temperature = seq.next_element()?.unwrap_or(None);
+                let sentence_splitter = seq.next_element()?.unwrap_or(None);
+                let word_cost_overrides = seq.next_element()?.unwrap_or_default();
 
                 Ok(TokenizerConfig {
                     dictionary,
                     user_dictionary,
                     mode,
+                    confidence_temperature,
+                    sentence_splitter,
+                    word_cost_overrides,
                 })
             }
 
@@ -147,6 +186,9 @@ impl<'de> Deserialize<'de> for TokenizerConfig {
                 let mut dictionary = None;
                 let mut user_dictionary = None;
                 let mut mode = None;
+                let mut confidence_temperature = None;
+                let mut sentence_splitter = None;
+                let mut word_cost_overrides = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::Dictionary => {
@@ -167,30 +209,117 @@ impl<'de> Deserialize<'de> for TokenizerConfig {
                             }
                             mode = Some(map.next_value()?);
                         }
+                        Field::ConfidenceTemperature => {
+                            if confidence_temperature.is_some() {
+                                return Err(de::Error::duplicate_field("confidence_temperature"));
+                            }
+                            confidence_temperature = Some(map.next_value()?);
+                        }
+                        Field::SentenceSplitter => {
+                            if sentence_splitter.is_some() {
+                                return Err(de::Error::duplicate_field("sentence_splitter"));
+                            }
+                            sentence_splitter = Some(map.next_value()?);
+                        }
+                        Field::WordCostOverrides => {
+                            if word_cost_overrides.is_some() {
+                                return Err(de::Error::duplicate_field("word_cost_overrides"));
+                            }
+                            word_cost_overrides = Some(map.next_value()?);
+                        }
                     }
                 }
                 let dictionary =
                     dictionary.ok_or_else(|| de::Error::missing_field("dictionary"))?;
                 let mode = mode.unwrap_or(Mode::Normal);
+                let word_cost_overrides = word_cost_overrides.unwrap_or_default();
                 Ok(TokenizerConfig {
                     dictionary,
                     user_dictionary,
                     mode,
+                    confidence_temperature,
+                    sentence_splitter,
+                    word_cost_overrides,
                 })
             }
         }
 
-        const FIELDS: &[&str] = &["dictionary", "user_dictionary", "mode"];
+        const FIELDS: &[&str] = &[
+            "dictionary",
+            "user_dictionary",
+            "mode",
+            "confidence_temperature",
+            "sentence_splitter",
+            "word_cost_overrides",
+        ];
         deserializer.deserialize_struct("TokenizerConfig", FIELDS, DurationVisitor)
     }
 }
 
+/// A caller-supplied constraint for partial parsing.
+/// Offsets are byte offsets into the text to be tokenized.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Constraint {
+    /// A token boundary must exist at the offset.
+    #[serde(rename = "boundary")]
+    Boundary(usize),
+    /// The text between `start` and `end` must be a single token.
+    /// If `pos` is given, only dictionary entries whose part-of-speech fields start with
+    /// its comma-separated values are allowed. If no allowed entry covers the span,
+    /// it is tokenized as an unknown word.
+    #[serde(rename = "span")]
+    Span {
+        start: usize,
+        end: usize,
+        pos: Option<String>,
+    },
+}
+
+/// A runtime override of the word cost of the dictionary entries with the surface.
+/// It applies to the entries of both the system and the user dictionaries, and
+/// takes effect without rebuilding them.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct WordCostOverride {
+    pub surface: String,
+    /// If given, only the entries whose part-of-speech fields start with its
+    /// comma-separated values are overridden, e.g. `名詞,固有名詞`.
+    #[serde(default)]
+    pub pos: Option<String>,
+    /// The new word cost of the entries.
+    #[serde(default)]
+    pub cost: Option<i16>,
+    /// Whether to keep the entries out of the lattice instead.
+    #[serde(default)]
+    pub ban: bool,
+}
+
+/// Reusable buffers for tokenizing.
+/// Keeping a workspace across calls avoids regrowing the lattice for every text.
+#[derive(Clone, Default)]
+pub struct TokenizerWorkspace {
+    lattice: Lattice,
+    path: Vec<EdgeId>,
+}
+
+impl TokenizerWorkspace {
+    /// Creates an empty workspace
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 #[derive(Clone)]
 /// Tokenizer
+///
+/// The dictionaries are shared between clones, so cloning a tokenizer is cheap.
 pub struct Tokenizer {
-    dictionary: Dictionary,
-    user_dictionary: Option<UserDictionary>,
+    dictionary: Arc<Dictionary>,
+    schema: Arc<DictionarySchema>,
+    user_dictionary: Option<Arc<UserDictionary>>,
     mode: Mode,
+    confidence_temperature: Option<f64>,
+    sentence_splitter: Box<dyn SentenceSplitter>,
+    word_cost_overrides: Arc<WordCostOverrides>,
 }
 
 impl Tokenizer {
@@ -209,48 +338,91 @@ impl Tokenizer {
     /// returns: Result<Tokenizer, LinderaError>
     ///
     pub fn with_config(config: TokenizerConfig) -> LinderaResult<Tokenizer> {
-        let dictionary = load_dictionary(config.dictionary)?;
+        if let Some(temperature) = config.confidence_temperature {
+            if temperature.is_nan() || temperature <= 0.0 {
+                return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "confidence temperature must be positive: {}",
+                    temperature
+                )));
+            }
+        }
+
+        let dictionary = Arc::new(load_dictionary(config.dictionary)?);
+        let schema = Arc::new(dictionary.schema.clone());
+
+        // The kind of a dictionary loaded from a path is taken from its header.
+        let sentence_splitter = match config.sentence_splitter {
+            Some(sentence_splitter_config) => sentence_splitter_config.into_sentence_splitter(),
+            None => default_sentence_splitter(dictionary.kind.as_ref()),
+        };
 
         let user_dictionary = match config.user_dictionary {
-            Some(user_dict_conf) => Some(load_user_dictionary(user_dict_conf)?),
+            Some(user_dict_conf) => Some(Arc::new(load_user_dictionary(user_dict_conf)?)),
             None => None,
         };
 
-        let tokenizer = Tokenizer {
+        let mut tokenizer = Tokenizer {
             dictionary,
+            schema,
             user_dictionary,
             mode: config.mode,
+            confidence_temperature: config.confidence_temperature,
+            sentence_splitter,
+            word_cost_overrides: Arc::new(WordCostOverrides::default()),
         };
+        if !config.word_cost_overrides.is_empty() {
+            tokenizer.word_cost_overrides =
+                Arc::new(tokenizer.resolve_word_cost_overrides(&config.word_cost_overrides)?);
+        }
 
         Ok(tokenizer)
     }
 
+    /// Replaces the sentence splitter.
+    ///
+    /// # Arguments
+    ///
+    /// * `sentence_splitter`: The sentence splitter used before tokenizing each sentence.
+    ///
+    pub fn set_sentence_splitter(&mut self, sentence_splitter: Box<dyn SentenceSplitter>) {
+        self.sentence_splitter = sentence_splitter;
+    }
+
+    /// Returns the sentence splitter.
+    pub fn sentence_splitter(&self) -> &dyn SentenceSplitter {
+        self.sentence_splitter.as_ref()
+    }
+
     fn word_detail(&self, word_id: WordId) -> LinderaResult<Vec<String>> {
         if word_id.is_unknown() {
-            return Ok(vec!["UNK".to_string()]);
+            return self
+                .dictionary
+                .unknown_dictionary
+                .word_details(word_id)
+                .map(|details| details.to_vec())
+                .ok_or_else(|| {
+                    LinderaErrorKind::Content.with_error(anyhow::anyhow!(
+                        "the unknown dictionary has no entry for the word {:?}",
+                        word_id
+                    ))
+                });
         }
 
         let (words_idx_data, words_data) = if word_id.is_system() {
             (
-                self.dictionary.words_idx_data.as_slice(),
-                self.dictionary.words_data.as_slice(),
+                &self.dictionary.words_idx_data[..],
+                &self.dictionary.words_data[..],
             )
         } else {
+            let user_dictionary = self.user_dictionary.as_ref().ok_or_else(|| {
+                LinderaErrorKind::Content.with_error(anyhow::anyhow!(
+                    "the word {:?} belongs to the user dictionary, but no user dictionary is loaded",
+                    word_id
+                ))
+            })?;
             (
-                self.user_dictionary
-                    .as_ref()
-                    .ok_or_else(|| {
-                        LinderaErrorKind::Content.with_error(anyhow::anyhow!("internal error."))
-                    })?
-                    .words_idx_data
-                    .as_slice(),
-                self.user_dictionary
-                    .as_ref()
-                    .ok_or_else(|| {
-                        LinderaErrorKind::Content.with_error(anyhow::anyhow!("internal error."))
-                    })?
-                    .words_data
-                    .as_slice(),
+                user_dictionary.words_idx_data.as_slice(),
+                user_dictionary.words_data.as_slice(),
             )
         };
         let idx = LittleEndian::read_u32(&words_idx_data[4 * word_id.0 as usize..][..4]);
@@ -261,59 +433,431 @@ impl Tokenizer {
         Ok(word_detail)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn append_tokens<'a>(
+        &self,
+        sentence: &'a str,
+        sentence_offset: usize,
+        lattice: &Lattice,
+        path: &[EdgeId],
+        confidences: Option<&[f64]>,
+        with_details: bool,
+        tokens: &mut Vec<Token<'a>>,
+    ) -> LinderaResult<()> {
+        for (i, &edge_id) in path.iter().enumerate() {
+            let edge = lattice.edge(edge_id);
+            let word_id = edge.word_entry.word_id;
+            let token_start = edge.start_index as usize;
+            let token_stop = edge.stop_index as usize;
+            let surface = &sentence[token_start..token_stop];
+            if surface == " " {
+                // skip whitespace
+                continue;
+            }
+            let token = Token {
+                text: Cow::Borrowed(surface),
+                details: if with_details {
+                    Some(self.word_detail(word_id)?)
+                } else {
+                    None
+                },
+                byte_start: sentence_offset + token_start,
+                byte_end: sentence_offset + token_stop,
+                confidence: confidences.map(|confidences| confidences[i]),
+                dictionary_kind: self.dictionary.kind.clone(),
+                schema: if with_details {
+                    Some(self.schema.clone())
+                } else {
+                    None
+                },
+                metadata: Some(TokenMetadata::from_edge(edge)),
+                ..Default::default()
+            };
+            if matches!(self.mode, Mode::Extended(_)) && edge.edge_type == EdgeType::UNKNOWN {
+                // Unknown words are split into unigrams of grapheme clusters,
+                // so that combining marks stay with their base characters.
+                for (offset, grapheme) in surface.grapheme_indices(true) {
+                    let mut unigram = token.clone();
+                    unigram.text = Cow::Borrowed(grapheme);
+                    unigram.byte_start = token.byte_start + offset;
+                    unigram.byte_end = unigram.byte_start + grapheme.len();
+                    tokens.push(unigram);
+                }
+            } else {
+                tokens.push(token);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_constraints(&self, text: &str, constraints: &[Constraint]) -> LinderaResult<()> {
+        let check_offset = |offset: usize| {
+            if offset > text.len() || !text.is_char_boundary(offset) {
+                return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "constraint offset {} is not a character boundary of the text",
+                    offset
+                )));
+            }
+            Ok(())
+        };
+
+        let mut spans = Vec::new();
+        for constraint in constraints {
+            match constraint {
+                Constraint::Boundary(offset) => check_offset(*offset)?,
+                Constraint::Span { start, end, .. } => {
+                    check_offset(*start)?;
+                    check_offset(*end)?;
+                    if start >= end {
+                        return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                            "constraint span {}..{} is empty",
+                            start,
+                            end
+                        )));
+                    }
+                    spans.push((*start, *end));
+                }
+            }
+        }
+
+        spans.sort_unstable();
+        for pair in spans.windows(2) {
+            if pair[0].1 > pair[1].0 {
+                return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "constraint spans {}..{} and {}..{} overlap",
+                    pair[0].0,
+                    pair[0].1,
+                    pair[1].0,
+                    pair[1].1
+                )));
+            }
+        }
+        for constraint in constraints {
+            if let Constraint::Boundary(offset) = constraint {
+                if let Some((start, end)) = spans
+                    .iter()
+                    .find(|(start, end)| start < offset && offset < end)
+                {
+                    return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                        "constraint boundary {} is inside span {}..{}",
+                        offset,
+                        start,
+                        end
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the overrides of the config into the overrides of the entries they match.
+    fn resolve_word_cost_overrides(
+        &self,
+        overrides: &[WordCostOverride],
+    ) -> LinderaResult<WordCostOverrides> {
+        let mut word_cost_overrides = WordCostOverrides::new();
+        for word_cost_override in overrides {
+            // The word cost of the entries, or `None` to ban them.
+            let cost = match (word_cost_override.ban, word_cost_override.cost) {
+                (true, None) => None,
+                (false, Some(cost)) => Some(cost),
+                _ => {
+                    return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                        "the word cost override of {:?} must have either a cost or a ban",
+                        word_cost_override.surface
+                    )));
+                }
+            };
+            let word_ids = self.constrained_word_ids(
+                &word_cost_override.surface,
+                word_cost_override.pos.as_deref(),
+            )?;
+            if word_ids.is_empty() {
+                return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "no dictionary entry matches the word cost override of {:?}",
+                    word_cost_override.surface
+                )));
+            }
+            for word_id in word_ids {
+                match cost {
+                    Some(cost) => word_cost_overrides.set_cost(word_id, cost),
+                    None => word_cost_overrides.ban(word_id),
+                }
+            }
+        }
+
+        Ok(word_cost_overrides)
+    }
+
+    /// Returns the IDs of the entries that cover the whole surface and match the part-of-speech.
+    /// All the entries covering the surface match if `pos` is `None`.
+    fn constrained_word_ids(&self, surface: &str, pos: Option<&str>) -> LinderaResult<Vec<WordId>> {
+        let pos: Vec<&str> = pos.map(|pos| pos.split(',').collect()).unwrap_or_default();
+        let mut word_ids = Vec::new();
+        let dicts = self
+            .user_dictionary
+            .as_ref()
+            .map(|d| &d.dict)
+            .into_iter()
+            .chain(std::iter::once(&self.dictionary.dict));
+        for dict in dicts {
+            for (prefix_len, word_entry) in dict.prefix(surface) {
+                if prefix_len != surface.len() {
+                    continue;
+                }
+                let details = self.word_detail(word_entry.word_id)?;
+                if pos.len() <= details.len() && pos.iter().zip(details.iter()).all(|(a, b)| a == b)
+                {
+                    word_ids.push(word_entry.word_id);
+                }
+            }
+        }
+
+        Ok(word_ids)
+    }
+
+    /// Builds the lattice constraints of the sentence starting at `sentence_offset`.
+    fn sentence_constraints(
+        &self,
+        sentence: &str,
+        sentence_offset: usize,
+        constraints: &[Constraint],
+    ) -> LinderaResult<Constraints> {
+        let sentence_end = sentence_offset + sentence.len();
+        let mut sentence_constraints = Constraints::default();
+        for constraint in constraints {
+            match constraint {
+                Constraint::Boundary(offset) => {
+                    if sentence_offset < *offset && *offset < sentence_end {
+                        sentence_constraints
+                            .boundaries
+                            .push(offset - sentence_offset);
+                    }
+                }
+                Constraint::Span { start, end, pos } => {
+                    if sentence_offset <= *start && *end <= sentence_end {
+                        let (start, end) = (start - sentence_offset, end - sentence_offset);
+                        let word_ids = match pos {
+                            Some(pos) => {
+                                Some(self.constrained_word_ids(&sentence[start..end], Some(pos))?)
+                            }
+                            None => None,
+                        };
+                        sentence_constraints.spans.push(ConstrainedSpan {
+                            start,
+                            end,
+                            word_ids,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(sentence_constraints)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn tokenize_sentence<'a>(
+        &self,
+        lattice: &mut Lattice,
+        path: &mut Vec<EdgeId>,
+        sentence: &'a str,
+        sentence_offset: usize,
+        constraints: &Constraints,
+        mode: &Mode,
+        with_details: bool,
+        tokens: &mut Vec<Token<'a>>,
+    ) -> LinderaResult<()> {
+        lattice.set_text_with_constraints(
+            &self.dictionary.dict,
+            &self.user_dictionary.as_ref().map(|d| &d.dict),
+            &self.dictionary.char_definitions,
+            &self.dictionary.unknown_dictionary,
+            sentence,
+            mode,
+            constraints,
+            &self.word_cost_overrides,
+        );
+        lattice.calculate_path_costs(&self.dictionary.cost_matrix, mode);
+
+        lattice.best_path_into(path);
+        let confidences = self
+            .confidence_temperature
+            .map(|temperature| lattice.marginals(&self.dictionary.cost_matrix, mode, temperature));
+        self.append_tokens(
+            sentence,
+            sentence_offset,
+            lattice,
+            path,
+            confidences.as_deref(),
+            with_details,
+            tokens,
+        )
+    }
+
     fn tokenize_process<'a>(
         &self,
+        workspace: &mut TokenizerWorkspace,
         text: &'a str,
+        constraints: &[Constraint],
         with_details: bool,
-    ) -> LinderaResult<Vec<Token<'a>>> {
-        let mut tokens: Vec<Token> = Vec::new();
-        let mut lattice = Lattice::default();
+        tokens: &mut Vec<Token<'a>>,
+    ) -> LinderaResult<()> {
+        self.validate_constraints(text, constraints)?;
 
-        // Split text into sentences using Japanese punctuation.
-        for sentence in text.split_inclusive(&['。', '、']) {
-            if text.is_empty() {
-                continue;
+        tokens.clear();
+        let TokenizerWorkspace { lattice, path } = workspace;
+
+        for (sentence_offset, sentence) in
+            split_sentences(self.sentence_splitter.as_ref(), text, constraints)
+        {
+            let sentence_constraints =
+                self.sentence_constraints(sentence, sentence_offset, constraints)?;
+            if let Mode::Search(_penalty) = &self.mode {
+                // The words of the normal mode are emitted along with their parts.
+                let mut words = Vec::new();
+                self.tokenize_sentence(
+                    lattice,
+                    path,
+                    sentence,
+                    sentence_offset,
+                    &sentence_constraints,
+                    &Mode::Normal,
+                    with_details,
+                    &mut words,
+                )?;
+                let mut parts = Vec::new();
+                self.tokenize_sentence(
+                    lattice,
+                    path,
+                    sentence,
+                    sentence_offset,
+                    &sentence_constraints,
+                    &self.mode,
+                    with_details,
+                    &mut parts,
+                )?;
+                append_search_tokens(words, parts, tokens);
+            } else {
+                self.tokenize_sentence(
+                    lattice,
+                    path,
+                    sentence,
+                    sentence_offset,
+                    &sentence_constraints,
+                    &self.mode,
+                    with_details,
+                    tokens,
+                )?;
             }
+        }
+        number_tokens(text, tokens);
+
+        Ok(())
+    }
+
+    fn tokenize_nbest_process<'a>(
+        &self,
+        text: &'a str,
+        n: usize,
+        with_details: bool,
+    ) -> LinderaResult<Vec<(Vec<Token<'a>>, i32)>> {
+        if let Mode::Search(_penalty) = &self.mode {
+            // The tokens of the search mode form a graph rather than a single segmentation.
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "N-best segmentations are not available in search mode"
+            )));
+        }
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut results: Vec<(Vec<Token>, i32)> = vec![(Vec::new(), 0)];
+        let mut lattice = Lattice::default();
 
-            lattice.set_text(
+        // Sentences are segmented independently, so the N-best lists of each sentence are
+        // combined and only the N lowest-cost combinations are kept.
+        for (sentence_offset, sentence) in
+            split_sentences(self.sentence_splitter.as_ref(), text, &[])
+        {
+            lattice.set_text_with_constraints(
                 &self.dictionary.dict,
                 &self.user_dictionary.as_ref().map(|d| &d.dict),
                 &self.dictionary.char_definitions,
                 &self.dictionary.unknown_dictionary,
                 sentence,
                 &self.mode,
+                &Constraints::default(),
+                &self.word_cost_overrides,
             );
             lattice.calculate_path_costs(&self.dictionary.cost_matrix, &self.mode);
 
-            let offsets = lattice.tokens_offset();
+            let mut sentence_results = Vec::new();
+            for (path, cost) in lattice.nbest_paths(&self.dictionary.cost_matrix, &self.mode, n) {
+                let mut sentence_tokens = Vec::new();
+                self.append_tokens(
+                    sentence,
+                    sentence_offset,
+                    &lattice,
+                    &path,
+                    None,
+                    with_details,
+                    &mut sentence_tokens,
+                )?;
+                sentence_results.push((sentence_tokens, cost));
+            }
 
-            for i in 0..offsets.len() {
-                let (token_start, word_id) = offsets[i];
-                let token_stop = if i == offsets.len() - 1 {
-                    sentence.len()
-                } else {
-                    let (next_start, _word_id) = offsets[i + 1];
-                    next_start
-                };
-                let surface = &sentence[token_start..token_stop];
-                if surface == " " {
-                    // skip whitespace
-                    continue;
+            let mut combined = Vec::with_capacity(results.len() * sentence_results.len());
+            for (tokens, cost) in &results {
+                for (sentence_tokens, sentence_cost) in &sentence_results {
+                    let mut new_tokens = tokens.clone();
+                    new_tokens.extend(sentence_tokens.iter().cloned());
+                    combined.push((new_tokens, cost + sentence_cost));
                 }
-                tokens.push(Token {
-                    text: Cow::Borrowed(surface),
-                    details: if with_details {
-                        Some(self.word_detail(word_id)?)
-                    } else {
-                        None
-                    },
-                    byte_start: token_start,
-                    byte_end: token_stop,
-                })
             }
+            combined.sort_by_key(|(_tokens, cost)| *cost);
+            combined.truncate(n);
+            results = combined;
+        }
+        for (tokens, _cost) in results.iter_mut() {
+            number_tokens(text, tokens);
         }
 
-        Ok(tokens)
+        Ok(results)
+    }
+
+    fn tokenize_batch_process<'a>(
+        &self,
+        texts: &[&'a str],
+        with_details: bool,
+    ) -> LinderaResult<Vec<Vec<Token<'a>>>> {
+        let tokenize = |workspace: &mut TokenizerWorkspace, text: &&'a str| {
+            let mut tokens = Vec::new();
+            self.tokenize_process(workspace, text, &[], with_details, &mut tokens)?;
+            Ok(tokens)
+        };
+
+        // Each worker thread keeps its own workspace while the dictionaries are shared.
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            texts
+                .par_iter()
+                .map_init(TokenizerWorkspace::new, tokenize)
+                .collect()
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            let mut workspace = TokenizerWorkspace::new();
+            texts
+                .iter()
+                .map(|text| tokenize(&mut workspace, text))
+                .collect()
+        }
     }
 
     /// Tokenize the text (without word details)
@@ -328,7 +872,15 @@ impl Tokenizer {
     /// * LinderaError : Error message with LinderaErrorKind
     ///
     pub fn tokenize<'a>(&self, text: &'a str) -> LinderaResult<Vec<Token<'a>>> {
-        self.tokenize_process(text, false)
+        let mut tokens = Vec::new();
+        self.tokenize_process(
+            &mut TokenizerWorkspace::new(),
+            text,
+            &[],
+            false,
+            &mut tokens,
+        )?;
+        Ok(tokens)
     }
 
     /// Tokenize the text (with word details)
@@ -343,78 +895,526 @@ impl Tokenizer {
     /// * LinderaError : Error message with LinderaErrorKind
     ///
     pub fn tokenize_with_details<'a>(&self, text: &'a str) -> LinderaResult<Vec<Token<'a>>> {
-        self.tokenize_process(text, true)
+        let mut tokens = Vec::new();
+        self.tokenize_process(&mut TokenizerWorkspace::new(), text, &[], true, &mut tokens)?;
+        Ok(tokens)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    #[cfg(any(
-        feature = "ipadic",
-        feature = "unidic",
-        feature = "ko-dic",
-        feature = "cc-cedict"
-    ))]
-    use std::{
-        fs::File,
-        io::{BufReader, Read},
-        path::PathBuf,
-    };
+    /// Tokenize the text into `tokens` (without word details), reusing the buffers of `workspace`
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace`: buffers kept across calls
+    /// * `text`: Japanese text
+    /// * `tokens`: the list that the tokens are written to. It is cleared first.
+    ///
+    /// returns: Result<(), LinderaError>
+    ///
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn tokenize_into<'a>(
+        &self,
+        workspace: &mut TokenizerWorkspace,
+        text: &'a str,
+        tokens: &mut Vec<Token<'a>>,
+    ) -> LinderaResult<()> {
+        self.tokenize_process(workspace, text, &[], false, tokens)
+    }
 
-    #[cfg(any(
-        feature = "ipadic",
-        feature = "unidic",
-        feature = "ko-dic",
-        feature = "cc-cedict"
-    ))]
-    use crate::{
-        mode::{Mode, Penalty},
-        tokenizer::{DictionaryConfig, Tokenizer, TokenizerConfig, UserDictionaryConfig},
-        DictionaryKind,
-    };
+    /// Tokenize the text into `tokens` (with word details), reusing the buffers of `workspace`
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace`: buffers kept across calls
+    /// * `text`: Japanese text
+    /// * `tokens`: the list that the tokens are written to. It is cleared first.
+    ///
+    /// returns: Result<(), LinderaError>
+    ///
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn tokenize_with_details_into<'a>(
+        &self,
+        workspace: &mut TokenizerWorkspace,
+        text: &'a str,
+        tokens: &mut Vec<Token<'a>>,
+    ) -> LinderaResult<()> {
+        self.tokenize_process(workspace, text, &[], true, tokens)
+    }
 
-    #[test]
-    #[cfg(feature = "ipadic")]
-    fn test_tokenize_config_ipadic_normal() {
-        let config_str = r#"
-        {
-            "dictionary": {
-                "kind": "ipadic"
-            },
-            "mode": "normal"
-        }
-        "#;
+    /// Tokenize each of the texts (without word details)
+    ///
+    /// With the `parallel` feature, the texts are tokenized on the rayon thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts`: Japanese texts
+    ///
+    /// returns: Result<Vec<Vec<Token>>, LinderaError>
+    ///
+    /// * Vec<Vec<Token>> : the list of `Token` of each text in the input order if succeeded
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn tokenize_batch<'a>(&self, texts: &[&'a str]) -> LinderaResult<Vec<Vec<Token<'a>>>> {
+        self.tokenize_batch_process(texts, false)
+    }
 
-        let config: TokenizerConfig = serde_json::from_str(config_str).unwrap();
-        assert_eq!(config.dictionary.kind, Some(DictionaryKind::IPADIC));
+    /// Tokenize each of the texts (with word details)
+    ///
+    /// With the `parallel` feature, the texts are tokenized on the rayon thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts`: Japanese texts
+    ///
+    /// returns: Result<Vec<Vec<Token>>, LinderaError>
+    ///
+    /// * Vec<Vec<Token>> : the list of `Token` of each text in the input order if succeeded
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn tokenize_batch_with_details<'a>(
+        &self,
+        texts: &[&'a str],
+    ) -> LinderaResult<Vec<Vec<Token<'a>>>> {
+        self.tokenize_batch_process(texts, true)
     }
 
-    #[test]
-    #[cfg(feature = "ipadic")]
-    fn test_tokenize_config_ipadic_decompose() {
-        let config_str = r#"
-        {
-            "dictionary": {
-                "kind": "ipadic"
-            },
-            "mode": {
-                "decompose": {
-                    "kanji_penalty_length_threshold": 2,
-                    "kanji_penalty_length_penalty": 3000,
-                    "other_penalty_length_threshold": 7,
-                    "other_penalty_length_penalty": 1700
-                }
-            }
-        }
-        "#;
+    /// Tokenize the text into the N lowest-cost segmentations (without word details)
+    ///
+    /// Not available in search mode, whose tokens are not a single segmentation.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: Japanese text
+    /// * `n`: the maximum number of segmentations to return
+    ///
+    /// returns: Result<Vec<(Vec<Token>, i32)>, LinderaError>
+    ///
+    /// * Vec<(Vec<Token>, i32)> : the segmentations and their total costs in ascending order of cost if succeeded
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn tokenize_nbest<'a>(
+        &self,
+        text: &'a str,
+        n: usize,
+    ) -> LinderaResult<Vec<(Vec<Token<'a>>, i32)>> {
+        self.tokenize_nbest_process(text, n, false)
+    }
 
-        let config: TokenizerConfig = serde_json::from_str(config_str).unwrap();
-        assert_eq!(config.dictionary.kind, Some(DictionaryKind::IPADIC));
+    /// Tokenize the text into the N lowest-cost segmentations (with word details)
+    ///
+    /// Not available in search mode, whose tokens are not a single segmentation.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: Japanese text
+    /// * `n`: the maximum number of segmentations to return
+    ///
+    /// returns: Result<Vec<(Vec<Token>, i32)>, LinderaError>
+    ///
+    /// * Vec<(Vec<Token>, i32)> : the segmentations and their total costs in ascending order of cost if succeeded
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn tokenize_nbest_with_details<'a>(
+        &self,
+        text: &'a str,
+        n: usize,
+    ) -> LinderaResult<Vec<(Vec<Token<'a>>, i32)>> {
+        self.tokenize_nbest_process(text, n, true)
     }
 
-    #[test]
-    #[cfg(feature = "ipadic")]
-    fn test_tokenize_ipadic() {
+    /// Tokenize the text so that the result satisfies the constraints (without word details)
+    ///
+    /// Connection costs are still taken into account across the constrained offsets,
+    /// unlike splitting the text beforehand.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: Japanese text
+    /// * `constraints`: forced token boundaries and spans
+    ///
+    /// returns: Result<Vec<Token>, LinderaError>
+    ///
+    /// * Vec<Token> : the list of `Token` if succeeded
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn tokenize_with_constraints<'a>(
+        &self,
+        text: &'a str,
+        constraints: &[Constraint],
+    ) -> LinderaResult<Vec<Token<'a>>> {
+        let mut tokens = Vec::new();
+        self.tokenize_process(
+            &mut TokenizerWorkspace::new(),
+            text,
+            constraints,
+            false,
+            &mut tokens,
+        )?;
+        Ok(tokens)
+    }
+
+    /// Tokenize the text so that the result satisfies the constraints (with word details)
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: Japanese text
+    /// * `constraints`: forced token boundaries and spans
+    ///
+    /// returns: Result<Vec<Token>, LinderaError>
+    ///
+    /// * Vec<Token> : the list of `Token` if succeeded
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn tokenize_with_constraints_and_details<'a>(
+        &self,
+        text: &'a str,
+        constraints: &[Constraint],
+    ) -> LinderaResult<Vec<Token<'a>>> {
+        let mut tokens = Vec::new();
+        self.tokenize_process(
+            &mut TokenizerWorkspace::new(),
+            text,
+            constraints,
+            true,
+            &mut tokens,
+        )?;
+        Ok(tokens)
+    }
+
+    /// Dump the lattice of each sentence of the text, with the costs of every edge
+    ///
+    /// In search mode, the lattice of the decompose mode is dumped,
+    /// so the best path gives the parts of compound words.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: Japanese text
+    ///
+    /// returns: Result<Vec<LatticeDump>, LinderaError>
+    ///
+    /// * Vec<LatticeDump> : the lattices of the sentences if succeeded
+    /// * LinderaError : Error message with LinderaErrorKind
+    ///
+    pub fn dump_lattice(&self, text: &str) -> LinderaResult<Vec<LatticeDump>> {
+        let mut dumps = Vec::new();
+        let mut lattice = Lattice::default();
+
+        for (_sentence_offset, sentence) in
+            split_sentences(self.sentence_splitter.as_ref(), text, &[])
+        {
+            lattice.set_text_with_constraints(
+                &self.dictionary.dict,
+                &self.user_dictionary.as_ref().map(|d| &d.dict),
+                &self.dictionary.char_definitions,
+                &self.dictionary.unknown_dictionary,
+                sentence,
+                &self.mode,
+                &Constraints::default(),
+                &self.word_cost_overrides,
+            );
+            lattice.calculate_path_costs(&self.dictionary.cost_matrix, &self.mode);
+            dumps.push(lattice.dump(sentence, &self.dictionary.cost_matrix, &self.mode));
+        }
+
+        Ok(dumps)
+    }
+}
+
+// Sets the positions of the tokens of the text and their character offsets.
+// A token spanning several positions, i.e. a word of the search mode, is followed by
+// its parts, which take those positions.
+fn number_tokens(text: &str, tokens: &mut [Token]) {
+    let mut position = 0;
+    for token in tokens.iter_mut() {
+        let position_length = token.position_length.unwrap_or(1);
+        token.position = Some(position);
+        token.position_length = Some(position_length);
+        if position_length == 1 {
+            position += 1;
+        }
+    }
+    set_char_offsets(text, tokens);
+}
+
+// Merges the tokens of the normal mode and of the decompose mode into a token graph.
+// The text is cut where both segmentations have a boundary. A piece that is a single word in
+// the normal mode but several parts in the decompose mode yields the word spanning the
+// positions of its parts, followed by the parts. Any other piece yields the parts only.
+fn append_search_tokens<'a>(
+    words: Vec<Token<'a>>,
+    parts: Vec<Token<'a>>,
+    tokens: &mut Vec<Token<'a>>,
+) {
+    let mut words = words.into_iter().peekable();
+    let mut parts = parts.into_iter().peekable();
+    while parts.peek().is_some() {
+        let mut piece_words = Vec::new();
+        let mut piece_parts = Vec::new();
+        let (mut words_end, mut parts_end) = (0, 0);
+        loop {
+            if words_end <= parts_end {
+                match words.next() {
+                    Some(word) => {
+                        words_end = word.byte_end;
+                        piece_words.push(word);
+                    }
+                    None => {
+                        // The segmentations do not end together, so the rest is one piece.
+                        piece_parts.extend(parts.by_ref());
+                        break;
+                    }
+                }
+            }
+            while parts_end < words_end {
+                match parts.next() {
+                    Some(part) => {
+                        parts_end = part.byte_end;
+                        piece_parts.push(part);
+                    }
+                    None => break,
+                }
+            }
+            if words_end == parts_end || parts.peek().is_none() {
+                break;
+            }
+        }
+
+        if piece_words.len() == 1 && piece_parts.len() > 1 {
+            let mut word = piece_words.remove(0);
+            word.position_length = Some(piece_parts.len());
+            tokens.push(word);
+        }
+        tokens.extend(piece_parts);
+    }
+}
+
+/// Splits text into sentences with the sentence splitter.
+/// Sentences are merged so that no constrained span crosses a sentence boundary.
+fn split_sentences<'a>(
+    sentence_splitter: &dyn SentenceSplitter,
+    text: &'a str,
+    constraints: &[Constraint],
+) -> Vec<(usize, &'a str)> {
+    let mut sentences = Vec::new();
+    let mut sentence_start = 0;
+    for range in sentence_splitter.split(text) {
+        let sentence_end = range.end;
+        let inside_span = constraints.iter().any(|constraint| match constraint {
+            Constraint::Span { start, end, .. } => *start < sentence_end && sentence_end < *end,
+            Constraint::Boundary(_) => false,
+        });
+        if !inside_span {
+            sentences.push((sentence_start, &text[sentence_start..sentence_end]));
+            sentence_start = sentence_end;
+        }
+    }
+    if sentence_start < text.len() {
+        sentences.push((sentence_start, &text[sentence_start..]));
+    }
+
+    sentences
+}
+
+/// Returns the source directory of the mini UniDic dictionary used by the tests.
+#[cfg(test)]
+pub(crate) fn mini_unidic_dictionary_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../resources")
+        .join("unidic_mini_dictionary")
+}
+
+/// Builds the mini UniDic dictionary into a temporary directory and creates a tokenizer using it.
+/// The dictionary path of the config is replaced; the directory lives as long as the returned `TempDir`.
+#[cfg(test)]
+pub(crate) fn mini_unidic_tokenizer(mut config: TokenizerConfig) -> (tempfile::TempDir, Tokenizer) {
+    let output_dir = tempfile::tempdir().unwrap();
+    crate::builder::build_dictionary(
+        DictionaryKind::UniDic,
+        &mini_unidic_dictionary_dir(),
+        output_dir.path(),
+    )
+    .unwrap();
+    config.dictionary = DictionaryConfig {
+        kind: None,
+        path: Some(output_dir.path().to_path_buf()),
+    };
+    let tokenizer = Tokenizer::with_config(config).unwrap();
+
+    (output_dir, tokenizer)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use lindera_core::viterbi::CategoryPenalty;
+
+    #[cfg(any(
+        feature = "ipadic",
+        feature = "unidic",
+        feature = "ko-dic",
+        feature = "cc-cedict"
+    ))]
+    use std::{
+        fs::File,
+        io::{BufReader, Read},
+        path::PathBuf,
+    };
+
+    #[cfg(any(
+        feature = "ipadic",
+        feature = "unidic",
+        feature = "ko-dic",
+        feature = "cc-cedict"
+    ))]
+    use crate::{
+        tokenizer::{DictionaryConfig, Tokenizer, UserDictionaryConfig},
+        DictionaryKind,
+    };
+
+    #[cfg(feature = "ipadic")]
+    use std::sync::Arc;
+
+    #[cfg(feature = "ipadic")]
+    use lindera_core::viterbi::EdgeType;
+
+    #[cfg(feature = "ipadic")]
+    use crate::{
+        sentence_splitter::SentenceSplitterConfig,
+        tokenizer::{TokenizerWorkspace, WordCostOverride},
+    };
+
+    use crate::{
+        error::LinderaErrorKind,
+        mode::{Mode, Penalty},
+        sentence_splitter::{
+            no_split::NoSplitSentenceSplitter,
+            rule_based::{RuleBasedSentenceSplitter, RuleBasedSentenceSplitterConfig},
+        },
+        tokenizer::{
+            append_search_tokens, mini_unidic_tokenizer, number_tokens, split_sentences,
+            Constraint, TokenizerConfig,
+        },
+        Token,
+    };
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_config_ipadic_normal() {
+        let config_str = r#"
+        {
+            "dictionary": {
+                "kind": "ipadic"
+            },
+            "mode": "normal"
+        }
+        "#;
+
+        let config: TokenizerConfig = serde_json::from_str(config_str).unwrap();
+        assert_eq!(config.dictionary.kind, Some(DictionaryKind::IPADIC));
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_config_ipadic_decompose() {
+        let config_str = r#"
+        {
+            "dictionary": {
+                "kind": "ipadic"
+            },
+            "mode": {
+                "decompose": {
+                    "kanji_penalty_length_threshold": 2,
+                    "kanji_penalty_length_penalty": 3000,
+                    "other_penalty_length_threshold": 7,
+                    "other_penalty_length_penalty": 1700
+                }
+            }
+        }
+        "#;
+
+        let config: TokenizerConfig = serde_json::from_str(config_str).unwrap();
+        assert_eq!(config.dictionary.kind, Some(DictionaryKind::IPADIC));
+    }
+
+    #[test]
+    fn test_tokenize_decompose_with_category_penalties() {
+        let tokenize = |penalty: Penalty| {
+            let (_dir, tokenizer) = mini_unidic_tokenizer(TokenizerConfig {
+                mode: Mode::Decompose(penalty),
+                ..TokenizerConfig::default()
+            });
+            tokenizer
+                .tokenize("トートバッグ")
+                .unwrap()
+                .iter()
+                .map(|token| token.text.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // The word is not longer than the threshold for other words.
+        assert_eq!(tokenize(Penalty::default()), vec!["トートバッグ"]);
+
+        // KATAKANA words longer than 3 characters are penalized per character.
+        let penalty = Penalty {
+            category_penalties: vec![CategoryPenalty {
+                category: "KATAKANA".to_string(),
+                length_threshold: 3,
+                length_penalty: 2000,
+            }],
+            ..Penalty::default()
+        };
+        assert_eq!(tokenize(penalty), vec!["トート", "バッグ"]);
+    }
+
+    #[test]
+    fn test_search_mode_nbest_and_dump_lattice() {
+        let tokenizer = |mode: Mode| {
+            mini_unidic_tokenizer(TokenizerConfig {
+                mode,
+                ..TokenizerConfig::default()
+            })
+        };
+        let (_search_dir, search) = tokenizer(Mode::Search(Penalty::default()));
+        let (_decompose_dir, decompose) = tokenizer(Mode::Decompose(Penalty::default()));
+        let text = "羽田空港限定トートバッグ";
+
+        let err = search.tokenize_nbest(text, 2).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Args);
+        assert!(decompose.tokenize_nbest(text, 2).is_ok());
+
+        assert_eq!(
+            search.dump_lattice(text).unwrap(),
+            decompose.dump_lattice(text).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tokenize_extended_mode_grapheme_clusters() {
+        let (_dir, tokenizer) = mini_unidic_tokenizer(TokenizerConfig {
+            mode: Mode::Extended(Penalty::default()),
+            ..TokenizerConfig::default()
+        });
+
+        // The voiced sound mark U+3099 combines with the preceding カ.
+        let text = "羽田空港のカ\u{3099}ラス";
+        let tokens = tokenizer.tokenize(text).unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
+            vec!["羽田", "空港", "の", "カ\u{3099}", "ラ", "ス"]
+        );
+        assert_eq!(tokens[3].byte_start, 15);
+        assert_eq!(tokens[3].byte_end, 21);
+        assert_eq!(tokens[4].byte_start, 21);
+        assert!(!tokens[2].is_unknown());
+        assert!(tokens[3].is_unknown());
+        assert!(tokens[5].is_unknown());
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_ipadic() {
         let dictionary = DictionaryConfig {
             kind: Some(DictionaryKind::IPADIC),
             path: None,
@@ -424,6 +1424,7 @@ mod tests {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -460,6 +1461,7 @@ mod tests {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -487,6 +1489,7 @@ mod tests {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -523,6 +1526,7 @@ mod tests {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -535,59 +1539,76 @@ mod tests {
 
     #[test]
     #[cfg(feature = "ipadic")]
-    fn test_tokenize_with_simple_userdic_ipadic() {
+    fn test_tokenize_token_features_ipadic() {
         let dictionary = DictionaryConfig {
             kind: Some(DictionaryKind::IPADIC),
             path: None,
         };
 
-        let userdic_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("../resources")
-            .join("ipadic_simple_userdic.csv");
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
 
-        let user_dictionary = Some(UserDictionaryConfig {
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let tokens = tokenizer.tokenize_with_details("東京に行った").unwrap();
+        assert_eq!(tokens[0].pos(), Some("名詞"));
+        assert_eq!(tokens[0].reading(), Some("トウキョウ"));
+        assert_eq!(tokens[2].base_form(), Some("行く"));
+        assert_eq!(tokens[2].reading(), Some("イッ"));
+
+        // Unknown words have the features of unk.def, laid out as the ones of the known words.
+        let tokens = tokenizer.tokenize_with_details("Linderaを使う").unwrap();
+        assert_eq!(tokens[0].text, "Lindera");
+        assert_eq!(tokens[0].details.as_ref().unwrap().len(), 9);
+        assert_eq!(tokens[0].pos(), Some("名詞"));
+        assert_eq!(tokens[0].reading(), None);
+
+        // No details, no features.
+        let tokens = tokenizer.tokenize("東京").unwrap();
+        assert_eq!(tokens[0].pos(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_get_detail_ipadic() {
+        let dictionary = DictionaryConfig {
             kind: Some(DictionaryKind::IPADIC),
-            path: userdic_file,
-        });
+            path: None,
+        };
 
         let config = TokenizerConfig {
             dictionary,
-            user_dictionary,
+            user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
-        let tokens = tokenizer
-            .tokenize("東京スカイツリーの最寄り駅はとうきょうスカイツリー駅です。")
-            .unwrap();
-        assert_eq!(
-            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
-            vec![
-                "東京スカイツリー",
-                "の",
-                "最寄り駅",
-                "は",
-                "とうきょうスカイツリー駅",
-                "です",
-                "。"
-            ]
-        );
+        let tokens = tokenizer.tokenize_with_details("東京に行った").unwrap();
+        assert_eq!(tokens[0].get_detail("reading"), Some("トウキョウ"));
+        assert_eq!(tokens[0].get_detail("pos2"), Some("地域"));
+        assert_eq!(tokens[0].get_detail("pinyin"), None);
+        let named_details = tokens[2].named_details().unwrap();
+        assert_eq!(named_details[6], ("base_form", "行く"));
     }
 
     #[test]
-    #[cfg(feature = "unidic")]
-    fn test_tokenize_with_simple_userdic_unidic() {
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_with_simple_userdic_ipadic() {
         let dictionary = DictionaryConfig {
-            kind: Some(DictionaryKind::UniDic),
+            kind: Some(DictionaryKind::IPADIC),
             path: None,
         };
 
         let userdic_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("../resources")
-            .join("unidic_simple_userdic.csv");
+            .join("ipadic_simple_userdic.csv");
 
         let user_dictionary = Some(UserDictionaryConfig {
-            kind: Some(DictionaryKind::UniDic),
+            kind: Some(DictionaryKind::IPADIC),
             path: userdic_file,
         });
 
@@ -595,6 +1616,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -606,8 +1628,7 @@ mod tests {
             vec![
                 "東京スカイツリー",
                 "の",
-                "最寄り",
-                "駅",
+                "最寄り駅",
                 "は",
                 "とうきょうスカイツリー駅",
                 "です",
@@ -617,19 +1638,19 @@ mod tests {
     }
 
     #[test]
-    #[cfg(feature = "ko-dic")]
-    fn test_tokenize_with_simple_userdic_ko_dic() {
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_token_metadata_ipadic() {
         let dictionary = DictionaryConfig {
-            kind: Some(DictionaryKind::KoDic),
+            kind: Some(DictionaryKind::IPADIC),
             path: None,
         };
 
         let userdic_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("../resources")
-            .join("ko-dic_simple_userdic.csv");
+            .join("ipadic_simple_userdic.csv");
 
         let user_dictionary = Some(UserDictionaryConfig {
-            kind: Some(DictionaryKind::KoDic),
+            kind: Some(DictionaryKind::IPADIC),
             path: userdic_file,
         });
 
@@ -637,13 +1658,242 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
-        let tokens = tokenizer.tokenize("하네다공항한정토트백.").unwrap();
-        assert_eq!(
-            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
-            vec!["하네다공항", "한정", "토트백", "."]
+        let tokens = tokenizer.tokenize("東京スカイツリーのLindera").unwrap();
+        assert_eq!(tokens.len(), 3);
+
+        let metadata = tokens[0].metadata.unwrap();
+        assert_eq!(metadata.edge_type, EdgeType::USER);
+        assert!(metadata.is_user());
+        assert!(!metadata.word_id.is_system());
+
+        let metadata = tokens[1].metadata.unwrap();
+        assert_eq!(metadata.edge_type, EdgeType::KNOWN);
+        assert!(metadata.word_id.is_system());
+        assert!(!tokens[1].is_unknown());
+
+        let metadata = tokens[2].metadata.unwrap();
+        assert_eq!(metadata.edge_type, EdgeType::UNKNOWN);
+        assert!(tokens[2].is_unknown());
+
+        // The path cost of a token adds its connection cost and word cost to the one of the
+        // previous token.
+        let mut path_cost = 0;
+        let mut right_context_id = 0;
+        for token in &tokens {
+            let metadata = token.metadata.unwrap();
+            path_cost += tokenizer
+                .dictionary
+                .cost_matrix
+                .cost(right_context_id, metadata.left_context_id)
+                + metadata.word_cost;
+            assert_eq!(metadata.path_cost, path_cost);
+            right_context_id = metadata.right_context_id;
+        }
+    }
+
+    #[test]
+    fn test_tokenize_token_metadata_path_cost() {
+        let (_dir, tokenizer) = mini_unidic_tokenizer(TokenizerConfig::default());
+        let tokens = tokenizer.tokenize("羽田空港の限定トートバッグ").unwrap();
+
+        // 羽田空港: 0 + 4000, の: -500 + 500, 限定: -200 + 3500, トートバッグ: 200 + 3000
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| {
+                    let metadata = token.metadata.unwrap();
+                    (token.text.as_ref(), metadata.word_cost, metadata.path_cost)
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                ("羽田空港", 4000, 4000),
+                ("の", 500, 4000),
+                ("限定", 3500, 7300),
+                ("トートバッグ", 3000, 10500),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_search_mode_ipadic() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Search(Penalty::default()),
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let tokens = tokenizer.tokenize("関西国際空港限定トートバッグ").unwrap();
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|t| (
+                    t.text.as_ref(),
+                    t.position.unwrap(),
+                    t.position_length.unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("関西国際空港", 0, 3),
+                ("関西", 0, 1),
+                ("国際", 1, 1),
+                ("空港", 2, 1),
+                ("限定", 3, 1),
+                ("トートバッグ", 4, 1)
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_extended_mode_ipadic() {
+        let config_str = r#"
+        {
+            "dictionary": {
+                "kind": "ipadic"
+            },
+            "mode": {
+                "extended": {
+                    "kanji_penalty_length_threshold": 2,
+                    "kanji_penalty_length_penalty": 3000,
+                    "other_penalty_length_threshold": 7,
+                    "other_penalty_length_penalty": 1700
+                }
+            }
+        }
+        "#;
+        let config: TokenizerConfig = serde_json::from_str(config_str).unwrap();
+        assert_eq!(config.mode, Mode::Extended(Penalty::default()));
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let tokens = tokenizer.tokenize("関西国際空港限定トートバッグ").unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
+            vec!["関西", "国際", "空港", "限定", "ト", "ー", "ト", "バ", "ッ", "グ"]
+        );
+        assert_eq!(tokens[5].byte_start, 27);
+        assert_eq!(tokens[5].byte_end, 30);
+        assert!(tokens[9].is_unknown());
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_offsets_ipadic() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        // "😀" is 4 bytes, 1 character and 2 UTF-16 code units.
+        let tokens = tokenizer.tokenize("😀東京").unwrap();
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|t| (
+                    t.text.as_ref(),
+                    t.char_start.unwrap(),
+                    t.char_end.unwrap(),
+                    t.utf16_start.unwrap(),
+                    t.utf16_end.unwrap(),
+                    t.position.unwrap(),
+                    t.position_length.unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            vec![("😀", 0, 1, 0, 2, 0, 1), ("東京", 1, 3, 2, 4, 1, 1)]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "unidic")]
+    fn test_tokenize_with_simple_userdic_unidic() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::UniDic),
+            path: None,
+        };
+
+        let userdic_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../resources")
+            .join("unidic_simple_userdic.csv");
+
+        let user_dictionary = Some(UserDictionaryConfig {
+            kind: Some(DictionaryKind::UniDic),
+            path: userdic_file,
+        });
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let tokens = tokenizer
+            .tokenize("東京スカイツリーの最寄り駅はとうきょうスカイツリー駅です。")
+            .unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
+            vec![
+                "東京スカイツリー",
+                "の",
+                "最寄り",
+                "駅",
+                "は",
+                "とうきょうスカイツリー駅",
+                "です",
+                "。"
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ko-dic")]
+    fn test_tokenize_with_simple_userdic_ko_dic() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::KoDic),
+            path: None,
+        };
+
+        let userdic_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../resources")
+            .join("ko-dic_simple_userdic.csv");
+
+        let user_dictionary = Some(UserDictionaryConfig {
+            kind: Some(DictionaryKind::KoDic),
+            path: userdic_file,
+        });
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let tokens = tokenizer.tokenize("하네다공항한정토트백.").unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
+            vec!["하네다공항", "한정", "토트백", "."]
         );
     }
 
@@ -668,6 +1918,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -699,6 +1950,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -740,6 +1992,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -782,6 +2035,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -813,6 +2067,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -844,6 +2099,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -885,6 +2141,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -927,6 +2184,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         Tokenizer::with_config(config).unwrap();
@@ -954,6 +2212,7 @@ mod tests {
             dictionary,
             user_dictionary,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         Tokenizer::with_config(config).unwrap();
@@ -971,6 +2230,7 @@ mod tests {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -993,6 +2253,7 @@ mod tests {
             dictionary,
             user_dictionary: None,
             mode: Mode::Decompose(Penalty::default()),
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();
@@ -1003,6 +2264,680 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_unknown_words_like_mecab() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+
+        // The expected segmentations are the output of MeCab 0.996 with mecab-ipadic-2.7.0.
+        for (text, expected) in [
+            (
+                "関西国際空港限定トートバッグ",
+                vec!["関西国際空港", "限定", "トートバッグ"],
+            ),
+            ("iPhoneを買った", vec!["iPhone", "を", "買っ", "た"]),
+            (
+                "Linderaは形態素解析エンジンです",
+                vec!["Lindera", "は", "形態素", "解析", "エンジン", "です"],
+            ),
+        ] {
+            let tokens = tokenizer.tokenize(text).unwrap();
+            assert_eq!(
+                tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_nbest() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let text = "羽田空港限定トートバッグ";
+        let results = tokenizer.tokenize_nbest(text, 3).unwrap();
+        assert_eq!(results.len(), 3);
+
+        // The best segmentation is the same as the one returned by `tokenize`.
+        let tokens = tokenizer.tokenize(text).unwrap();
+        assert_eq!(
+            results[0]
+                .0
+                .iter()
+                .map(|t| t.text.as_ref())
+                .collect::<Vec<_>>(),
+            tokens.iter().map(|t| t.text.as_ref()).collect::<Vec<_>>()
+        );
+
+        // Segmentations are sorted by cost and all of them cover the whole text.
+        for i in 0..results.len() {
+            if i > 0 {
+                assert!(results[i - 1].1 <= results[i].1);
+            }
+            assert_eq!(
+                results[i]
+                    .0
+                    .iter()
+                    .map(|t| t.text.as_ref())
+                    .collect::<String>(),
+                text
+            );
+        }
+
+        assert!(tokenizer.tokenize_nbest(text, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_byte_offsets_across_sentences() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let text = "日本語の形態素解析を行うことができます。テスト。";
+        let tokens = tokenizer.tokenize(text).unwrap();
+        for token in tokens.iter() {
+            assert_eq!(&text[token.byte_start..token.byte_end], token.text);
+        }
+    }
+
+    #[test]
+    fn test_tokenize_byte_offsets_relative_to_text() {
+        let (_dir, tokenizer) = mini_unidic_tokenizer(TokenizerConfig::default());
+        let text = "東京。羽田空港の限定トートバッグ。";
+        let expected = vec![
+            ("東京", 0, 6),
+            ("。", 6, 9),
+            ("羽田空港", 9, 21),
+            ("の", 21, 24),
+            ("限定", 24, 30),
+            ("トートバッグ", 30, 48),
+            ("。", 48, 51),
+        ];
+        let offsets = |tokens: &[Token]| {
+            tokens
+                .iter()
+                .map(|token| (token.text.to_string(), token.byte_start, token.byte_end))
+                .collect::<Vec<_>>()
+        };
+        let expected = expected
+            .into_iter()
+            .map(|(text, start, end)| (text.to_string(), start, end))
+            .collect::<Vec<_>>();
+
+        // The offsets of the tokens of the second sentence start at the end of the first one.
+        assert_eq!(offsets(&tokenizer.tokenize(text).unwrap()), expected);
+        let nbest = tokenizer.tokenize_nbest(text, 1).unwrap();
+        assert_eq!(offsets(&nbest[0].0), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_with_constraints() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let text = "関西国際空港限定トートバッグ";
+
+        // A forced boundary splits "関西国際空港".
+        let tokens = tokenizer
+            .tokenize_with_constraints(text, &[Constraint::Boundary(6)])
+            .unwrap();
+        assert_eq!(tokens[0].text, "関西");
+        assert!(tokens.iter().any(|t| t.byte_start == 6));
+        assert_eq!(
+            tokens.iter().map(|t| t.text.as_ref()).collect::<String>(),
+            text
+        );
+
+        // A forced span with a part-of-speech keeps the matching entry.
+        let tokens = tokenizer
+            .tokenize_with_constraints_and_details(
+                text,
+                &[Constraint::Span {
+                    start: 0,
+                    end: 18,
+                    pos: Some("名詞,固有名詞".to_string()),
+                }],
+            )
+            .unwrap();
+        assert_eq!(tokens[0].text, "関西国際空港");
+        assert_eq!(
+            tokens[0].details.as_ref().unwrap()[..2],
+            ["名詞".to_string(), "固有名詞".to_string()]
+        );
+
+        // A forced span without any dictionary entry becomes a single unknown word.
+        let tokens = tokenizer
+            .tokenize_with_constraints_and_details(
+                text,
+                &[Constraint::Span {
+                    start: 18,
+                    end: 42,
+                    pos: None,
+                }],
+            )
+            .unwrap();
+        let last = tokens.last().unwrap();
+        assert_eq!(last.text, "限定トートバッグ");
+        assert_eq!(last.byte_start, 18);
+        let details = last.details.as_ref().unwrap();
+        assert_eq!(details.len(), 9);
+        assert_eq!(details[0], "名詞");
+
+        // Overlapping spans and offsets inside a character are rejected.
+        let err = tokenizer
+            .tokenize_with_constraints(
+                text,
+                &[
+                    Constraint::Span {
+                        start: 0,
+                        end: 12,
+                        pos: None,
+                    },
+                    Constraint::Span {
+                        start: 6,
+                        end: 18,
+                        pos: None,
+                    },
+                ],
+            )
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Args);
+        let err = tokenizer
+            .tokenize_with_constraints(text, &[Constraint::Boundary(1)])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Args);
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_with_word_cost_overrides() {
+        let config_str = r#"
+        {
+            "dictionary": {
+                "kind": "ipadic"
+            },
+            "word_cost_overrides": [
+                {
+                    "surface": "関西国際空港",
+                    "pos": "名詞,固有名詞",
+                    "ban": true
+                }
+            ]
+        }
+        "#;
+        let mut config: TokenizerConfig = serde_json::from_str(config_str).unwrap();
+        assert_eq!(
+            config.word_cost_overrides,
+            vec![WordCostOverride {
+                surface: "関西国際空港".to_string(),
+                pos: Some("名詞,固有名詞".to_string()),
+                cost: None,
+                ban: true,
+            }]
+        );
+        let text = "関西国際空港限定トートバッグ";
+
+        // A banned entry is replaced by its parts.
+        let tokenizer = Tokenizer::with_config(config.clone()).unwrap();
+        let tokens = tokenizer.tokenize(text).unwrap();
+        assert_eq!(tokens[0].text, "関西");
+        assert!(tokens.iter().all(|t| t.text != "関西国際空港"));
+
+        // So is an entry made too expensive.
+        config.word_cost_overrides[0].ban = false;
+        config.word_cost_overrides[0].cost = Some(i16::MAX);
+        let tokenizer = Tokenizer::with_config(config.clone()).unwrap();
+        let tokens = tokenizer.tokenize(text).unwrap();
+        assert_eq!(tokens[0].text, "関西");
+        assert!(tokens.iter().all(|t| t.text != "関西国際空港"));
+
+        // The part-of-speech filter must match an entry.
+        config.word_cost_overrides[0].pos = Some("動詞".to_string());
+        let err = Tokenizer::with_config(config.clone()).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Args);
+
+        // An override needs either a cost or a ban.
+        config.word_cost_overrides[0].pos = None;
+        config.word_cost_overrides[0].cost = None;
+        let err = Tokenizer::with_config(config).err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Args);
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_with_confidence() {
+        let config_str = r#"
+        {
+            "dictionary": {
+                "kind": "ipadic"
+            },
+            "mode": "normal",
+            "confidence_temperature": 500.0
+        }
+        "#;
+        let config: TokenizerConfig = serde_json::from_str(config_str).unwrap();
+        assert_eq!(config.confidence_temperature, Some(500.0));
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let tokens = tokenizer.tokenize("関西国際空港限定トートバッグ").unwrap();
+        for token in &tokens {
+            let confidence = token.confidence.unwrap();
+            assert!(confidence > 0.0 && confidence <= 1.0);
+        }
+
+        // Confidence scores are not computed unless enabled.
+        let tokenizer = Tokenizer::new().unwrap();
+        let tokens = tokenizer.tokenize("関西国際空港限定トートバッグ").unwrap();
+        assert!(tokens.iter().all(|token| token.confidence.is_none()));
+
+        let config = TokenizerConfig {
+            confidence_temperature: Some(0.0),
+            ..TokenizerConfig::default()
+        };
+        assert!(Tokenizer::with_config(config).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_dump_lattice() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let text = "関西国際空港限定トートバッグ、すもももももももものうち";
+        let dumps = tokenizer.dump_lattice(text).unwrap();
+        assert_eq!(dumps.len(), 2);
+
+        // The edges on the best path are the tokens.
+        let tokens = tokenizer.tokenize(text).unwrap();
+        let mut best_path = Vec::new();
+        for dump in &dumps {
+            let mut edges = dump
+                .edges
+                .iter()
+                .filter(|edge| edge.best_path && edge.id > 1)
+                .collect::<Vec<_>>();
+            edges.sort_by_key(|edge| edge.start);
+            best_path.extend(edges.iter().map(|edge| edge.surface.clone()));
+        }
+        assert_eq!(
+            best_path,
+            tokens
+                .iter()
+                .map(|t| t.text.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert!(dumps[0].to_dot().contains("関西国際空港"));
+    }
+
+    #[test]
+    fn test_append_search_tokens() {
+        let token = |text: &'static str, byte_start: usize| Token {
+            text: Cow::Borrowed(text),
+            details: None,
+            byte_start,
+            byte_end: byte_start + text.len(),
+            ..Default::default()
+        };
+        let text = "abcdef gh";
+        let words = vec![
+            token("abc", 0),
+            token("de", 3),
+            token("f", 5),
+            token("gh", 7),
+        ];
+        let parts = vec![
+            token("a", 0),
+            token("b", 1),
+            token("c", 2),
+            token("def", 3),
+            token("gh", 7),
+        ];
+
+        let mut tokens = Vec::new();
+        append_search_tokens(words, parts, &mut tokens);
+        number_tokens(text, &mut tokens);
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|t| (
+                    t.text.as_ref(),
+                    t.position.unwrap(),
+                    t.position_length.unwrap()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("abc", 0, 3),
+                ("a", 0, 1),
+                ("b", 1, 1),
+                ("c", 2, 1),
+                ("def", 3, 1),
+                ("gh", 4, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        let sentence_splitter =
+            RuleBasedSentenceSplitter::new(RuleBasedSentenceSplitterConfig::japanese());
+        let text = "東京、大阪。名古屋";
+        assert_eq!(
+            split_sentences(&sentence_splitter, text, &[]),
+            vec![(0, "東京、"), (9, "大阪。"), (18, "名古屋")]
+        );
+
+        // A span across "、" keeps both sentences together.
+        let constraints = [Constraint::Span {
+            start: 6,
+            end: 12,
+            pos: None,
+        }];
+        assert_eq!(
+            split_sentences(&sentence_splitter, text, &constraints),
+            vec![(0, "東京、大阪。"), (18, "名古屋")]
+        );
+
+        assert_eq!(
+            split_sentences(&NoSplitSentenceSplitter::new(), text, &[]),
+            vec![(0, "東京、大阪。名古屋")]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_into_ipadic() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let mut workspace = TokenizerWorkspace::new();
+
+        // The workspace is reused from a longer text to a shorter one.
+        for text in [
+            "日本語の形態素解析を行うことができます。テスト。",
+            "関西国際空港",
+            "",
+        ] {
+            let mut tokens = vec![];
+            tokenizer
+                .tokenize_with_details_into(&mut workspace, text, &mut tokens)
+                .unwrap();
+            let expected = tokenizer.tokenize_with_details(text).unwrap();
+            assert_eq!(
+                tokens
+                    .iter()
+                    .map(|t| (t.text.as_ref(), t.byte_start, t.details.clone()))
+                    .collect::<Vec<_>>(),
+                expected
+                    .iter()
+                    .map(|t| (t.text.as_ref(), t.byte_start, t.details.clone()))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_batch_ipadic() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+
+        let texts = [
+            "日本語の形態素解析を行うことができます。",
+            "",
+            "関西国際空港",
+            "すもももももももものうち",
+        ];
+        let batch = tokenizer.tokenize_batch_with_details(&texts).unwrap();
+        assert_eq!(batch.len(), texts.len());
+        for (tokens, text) in batch.iter().zip(texts) {
+            let expected = tokenizer.tokenize_with_details(text).unwrap();
+            assert_eq!(
+                tokens
+                    .iter()
+                    .map(|t| (t.text.as_ref(), t.details.clone()))
+                    .collect::<Vec<_>>(),
+                expected
+                    .iter()
+                    .map(|t| (t.text.as_ref(), t.details.clone()))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenizer_clone_shares_dictionary() {
+        let dictionary = DictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: None,
+        };
+
+        let userdic_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../resources")
+            .join("ipadic_simple_userdic.csv");
+
+        let user_dictionary = Some(UserDictionaryConfig {
+            kind: Some(DictionaryKind::IPADIC),
+            path: userdic_file,
+        });
+
+        let config = TokenizerConfig {
+            dictionary,
+            user_dictionary,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer = Tokenizer::with_config(config).unwrap();
+        let cloned = tokenizer.clone();
+
+        // The clone points at the same dictionary data instead of copying it.
+        assert!(Arc::ptr_eq(&tokenizer.dictionary, &cloned.dictionary));
+        assert_eq!(Arc::strong_count(&tokenizer.dictionary), 2);
+        assert!(Arc::ptr_eq(
+            tokenizer.user_dictionary.as_ref().unwrap(),
+            cloned.user_dictionary.as_ref().unwrap()
+        ));
+        assert_eq!(
+            tokenizer.dictionary.dict.vals_data.as_ptr(),
+            cloned.dictionary.dict.vals_data.as_ptr()
+        );
+
+        drop(tokenizer);
+        assert_eq!(Arc::strong_count(&cloned.dictionary), 1);
+        assert!(!cloned.tokenize("関西国際空港").unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenizers_borrow_embedded_dictionary() {
+        let config = TokenizerConfig {
+            dictionary: DictionaryConfig {
+                kind: Some(DictionaryKind::IPADIC),
+                path: None,
+            },
+            user_dictionary: None,
+            mode: Mode::Normal,
+            ..TokenizerConfig::default()
+        };
+
+        let tokenizer1 = Tokenizer::with_config(config.clone()).unwrap();
+        let tokenizer2 = Tokenizer::with_config(config).unwrap();
+
+        // Both tokenizers borrow the data embedded in the binary instead of copying it.
+        assert!(!Arc::ptr_eq(&tokenizer1.dictionary, &tokenizer2.dictionary));
+        for (data1, data2) in [
+            (
+                &tokenizer1.dictionary.words_idx_data,
+                &tokenizer2.dictionary.words_idx_data,
+            ),
+            (
+                &tokenizer1.dictionary.words_data,
+                &tokenizer2.dictionary.words_data,
+            ),
+            (
+                &tokenizer1.dictionary.dict.vals_data,
+                &tokenizer2.dictionary.dict.vals_data,
+            ),
+            (
+                &tokenizer1.dictionary.cost_matrix.costs_data,
+                &tokenizer2.dictionary.cost_matrix.costs_data,
+            ),
+        ] {
+            assert!(data1.is_borrowed());
+            assert_eq!(data1.as_ptr(), data2.as_ptr());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ipadic")]
+    fn test_tokenize_with_sentence_splitter_config() {
+        let config_str = r#"
+        {
+            "dictionary": {
+                "kind": "ipadic"
+            },
+            "sentence_splitter": {
+                "kind": "no_split"
+            }
+        }
+        "#;
+        let config: TokenizerConfig = serde_json::from_str(config_str).unwrap();
+        assert_eq!(
+            config.sentence_splitter,
+            Some(SentenceSplitterConfig::NoSplit)
+        );
+
+        let mut tokenizer = Tokenizer::with_config(config).unwrap();
+        let text = "「東京へ行く。」と言った。";
+        let texts = |tokens: &[Token]| {
+            tokens
+                .iter()
+                .map(|token| token.text.to_string())
+                .collect::<Vec<_>>()
+        };
+        let segmentation = vec![
+            "「", "東京", "へ", "行く", "。", "」", "と", "言っ", "た", "。",
+        ];
+
+        // The whole text is a single sentence.
+        assert_eq!(
+            split_sentences(tokenizer.sentence_splitter(), text, &[]),
+            vec![(0, text)]
+        );
+        assert_eq!(texts(&tokenizer.tokenize(text).unwrap()), segmentation);
+
+        // The terminator inside the brackets does not end a sentence.
+        tokenizer.set_sentence_splitter(Box::new(RuleBasedSentenceSplitter::new(
+            RuleBasedSentenceSplitterConfig::japanese(),
+        )));
+        assert_eq!(
+            split_sentences(tokenizer.sentence_splitter(), text, &[]),
+            vec![(0, text)]
+        );
+        assert_eq!(texts(&tokenizer.tokenize(text).unwrap()), segmentation);
+
+        // Without brackets, it does.
+        tokenizer.set_sentence_splitter(Box::new(RuleBasedSentenceSplitter::new(
+            RuleBasedSentenceSplitterConfig::new(vec!['。'], vec![], vec![], false),
+        )));
+        assert_eq!(
+            split_sentences(tokenizer.sentence_splitter(), text, &[]),
+            vec![(0, "「東京へ行く。"), (21, "」と言った。")]
+        );
+        assert_eq!(texts(&tokenizer.tokenize(text).unwrap()), segmentation);
+
+        // A sentence boundary is a token boundary, even inside a word.
+        tokenizer.set_sentence_splitter(Box::new(RuleBasedSentenceSplitter::new(
+            RuleBasedSentenceSplitterConfig::new(vec!['東'], vec![], vec![], false),
+        )));
+        assert_eq!(
+            split_sentences(tokenizer.sentence_splitter(), text, &[]),
+            vec![(0, "「東"), (6, "京へ行く。」と言った。")]
+        );
+        assert_eq!(
+            texts(&tokenizer.tokenize(text).unwrap())[..4],
+            ["「", "東", "京", "へ"]
+        );
+    }
+
     #[test]
     #[cfg(feature = "ipadic")]
     fn test_long_text() {
@@ -1026,6 +2961,7 @@ mod tests {
             dictionary,
             user_dictionary: None,
             mode: Mode::Normal,
+            ..TokenizerConfig::default()
         };
 
         let tokenizer = Tokenizer::with_config(config).unwrap();